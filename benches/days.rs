@@ -0,0 +1,155 @@
+//! Benchmarks of parsing and both parts of every day, run against the
+//! committed inputs and against generated inputs with more records.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use advent_of_code_2021::generate::{self, Options};
+use advent_of_code_2021::puzzle::day01::{self, SonarSweep};
+use advent_of_code_2021::puzzle::day02::{Dive, Submarine};
+use advent_of_code_2021::puzzle::day03::{BinaryDiagnostic, Decoder};
+use advent_of_code_2021::puzzle::day04::{self, GiantSquid};
+use advent_of_code_2021::puzzle::{self, Solution};
+use advent_of_code_2021::util;
+
+const SCALES: [usize; 3] = [1, 10, 100];
+const SEED: u64 = 2021;
+
+/// The committed input at scale 1, otherwise a generated input with `scale`
+/// times as many records. Repeating the committed records instead would
+/// leave day 3 with duplicate diagnostics that its ratings cannot tell apart.
+fn scaled_input<S: Solution>(scale: usize) -> String {
+    let committed = util::file2str(puzzle::input_dir().join(S::INPUT)).unwrap();
+    if scale == 1 {
+        return committed;
+    }
+    let lines = committed.lines().filter(|line| !line.trim().is_empty());
+    let options = match S::DAY {
+        3 => {
+            let size = lines.count() * scale;
+            // Room for twice as many distinct diagnostics as are drawn.
+            let bits = (usize::BITS - (2 * size - 1).leading_zeros()) as usize;
+            let width = committed.lines().next().unwrap().trim().len();
+            Options {
+                size,
+                bits: bits.max(width),
+                ..Options::default()
+            }
+        }
+        4 => Options {
+            size: (committed.split("\n\n").count() - 1) * scale,
+            ..Options::default()
+        },
+        _ => Options {
+            size: lines.count() * scale,
+            ..Options::default()
+        },
+    };
+    generate::generate(S::DAY, SEED, &options).unwrap()
+}
+
+fn sonar_sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day01");
+    for scale in SCALES {
+        let input = scaled_input::<SonarSweep>(scale);
+        let depths = SonarSweep::parse(&input).unwrap();
+        group.bench_with_input(BenchmarkId::new("parse", scale), &input, |b, input| {
+            b.iter(|| SonarSweep::parse(black_box(input)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sonar_sweep", scale),
+            &depths,
+            |b, depths| b.iter(|| day01::sonar_sweep(black_box(depths))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sonar_sweep_sliding_sum", scale),
+            &depths,
+            |b, depths| b.iter(|| day01::sonar_sweep_sliding_sum(black_box(depths))),
+        );
+    }
+    group.finish();
+}
+
+fn dive(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day02");
+    for scale in SCALES {
+        let input = scaled_input::<Dive>(scale);
+        let instructions = Dive::parse(&input).unwrap();
+        group.bench_with_input(BenchmarkId::new("parse", scale), &input, |b, input| {
+            b.iter(|| Dive::parse(black_box(input)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("execute", scale),
+            &instructions,
+            |b, instructions| {
+                b.iter(|| {
+                    let mut sub = Submarine::new();
+                    for instruction in black_box(instructions) {
+                        sub.execute(*instruction).unwrap();
+                    }
+                    sub.horizontal_position()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("execute_with_aim", scale),
+            &instructions,
+            |b, instructions| {
+                b.iter(|| {
+                    let mut sub = Submarine::new();
+                    for instruction in black_box(instructions) {
+                        sub.execute_with_aim(*instruction).unwrap();
+                    }
+                    sub.horizontal_position()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn binary_diagnostic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day03");
+    for scale in SCALES {
+        let input = scaled_input::<BinaryDiagnostic>(scale);
+        let report = BinaryDiagnostic::parse(&input).unwrap();
+        // Decoding must reach both ratings, not the path that gives up.
+        BinaryDiagnostic::gold(&report).unwrap();
+        group.bench_with_input(BenchmarkId::new("parse", scale), &input, |b, input| {
+            b.iter(|| BinaryDiagnostic::parse(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("decode", scale), &report, |b, report| {
+            b.iter_batched(
+                || report.diagnostics.clone(),
+                |diagnostics| {
+                    let mut decoder = Decoder::new(report.significant_bits);
+                    decoder.decode(diagnostics);
+                    decoder
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn giant_squid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day04");
+    for scale in SCALES {
+        let input = scaled_input::<GiantSquid>(scale);
+        let game = GiantSquid::parse(&input).unwrap();
+        group.bench_with_input(BenchmarkId::new("parse", scale), &input, |b, input| {
+            b.iter(|| GiantSquid::parse(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("bingo", scale), &game, |b, game| {
+            b.iter_batched(
+                || game.boards.clone(),
+                |mut boards| day04::bingo(&game.draw_numbers, &mut boards),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sonar_sweep, dive, binary_diagnostic, giant_squid);
+criterion_main!(benches);
@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code_2021::puzzle::day02::line2instruction;
+use advent_of_code_2021::util::lines;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for line in lines(input) {
+        let _ = line2instruction(line);
+    }
+});
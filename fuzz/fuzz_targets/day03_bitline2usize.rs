@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code_2021::puzzle::day03::bitline2usize;
+use advent_of_code_2021::util::lines;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for line in lines(input) {
+        let _ = bitline2usize(line);
+    }
+});
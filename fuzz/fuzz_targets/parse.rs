@@ -0,0 +1,17 @@
+//! Whole inputs through the parser of every day.
+
+#![no_main]
+
+use advent_of_code_2021::puzzle::day01::SonarSweep;
+use advent_of_code_2021::puzzle::day02::Dive;
+use advent_of_code_2021::puzzle::day03::BinaryDiagnostic;
+use advent_of_code_2021::puzzle::day04::GiantSquid;
+use advent_of_code_2021::puzzle::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = SonarSweep::parse(input);
+    let _ = Dive::parse(input);
+    let _ = BinaryDiagnostic::parse(input);
+    let _ = GiantSquid::parse(input);
+});
@@ -0,0 +1,26 @@
+#![no_main]
+
+use advent_of_code_2021::util::{
+    binary, comma_list, grid, header_and_sections, keyword_value, lines, str2vec_digits,
+    str2vec_numbers, str2vec_numbers_strict, str2vecinception, whitespace_list,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = str2vec_digits::<u8>(input);
+    let _ = str2vec_numbers::<u8>(input);
+    let _ = str2vec_numbers_strict::<u8>(input);
+    let _ = str2vecinception::<u8>(input);
+    for line in lines(input) {
+        let _ = comma_list::<u8>(line);
+        let _ = whitespace_list::<u8>(line);
+        let _ = keyword_value::<u8>(line);
+        let _ = binary::<u8>(line);
+    }
+    if let Ok((header, sections)) = header_and_sections(input) {
+        let _ = grid::<u8>(&header);
+        for section in sections {
+            let _ = grid::<u8>(&section);
+        }
+    }
+});
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::puzzle::Part;
+use crate::util::{file2str, lines};
+
+/// Accepted answers read from a TOML file with one table per day:
+///
+/// ```toml
+/// [day01]
+/// silver = 1665
+/// gold = "1702"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, Part), String>,
+}
+
+impl Answers {
+    pub fn default_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let input = file2str(path)?;
+        Self::parse(&input).map_err(|error| error.in_file(path))
+    }
+
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut answers = BTreeMap::new();
+        let mut day = None;
+        for line in lines(input) {
+            let text = strip_comment(line.text).trim();
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(table) = text.strip_prefix('[') {
+                let name = table
+                    .strip_suffix(']')
+                    .ok_or_else(|| line.error_at(text, "expected `]` after the table name"))?;
+                let number = name
+                    .strip_prefix("day")
+                    .and_then(|number| number.parse::<u8>().ok())
+                    .ok_or_else(|| {
+                        let message = format!("`{}` is not a table such as `day01`", name);
+                        line.error_at(text, message)
+                    })?;
+                day = Some(number);
+                continue;
+            }
+
+            let (key, value) = text
+                .split_once('=')
+                .ok_or_else(|| line.error_at(text, "expected `<part> = <answer>`"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let part = match key {
+                "silver" => Part::Silver,
+                "gold" => Part::Gold,
+                _ => {
+                    let message = format!("unknown part `{}`", key);
+                    return Err(line.error_at(key, message));
+                }
+            };
+            let day = day.ok_or_else(|| line.error_at(key, "answer outside of a day table"))?;
+            let value = match value.strip_prefix('"') {
+                Some(quoted) => quoted
+                    .strip_suffix('"')
+                    .ok_or_else(|| line.error_at(value, "unterminated string"))?,
+                None if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) => value,
+                None => {
+                    let message = format!("`{}` is not a number or a string", value);
+                    return Err(line.error_at(value, message));
+                }
+            };
+            answers.insert((day, part), String::from(value));
+        }
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+}
+
+/// The text before a `#` that is not inside a quoted value.
+fn strip_comment(text: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &text[..index],
+            _ => {}
+        }
+    }
+    text
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+pub fn verdict(answers: &Answers, day: u8, part: Part, value: &str) -> Verdict {
+    match answers.get(day, part) {
+        Some(expected) if expected == value => Verdict::Pass,
+        Some(expected) => Verdict::Fail {
+            expected: String::from(expected),
+        },
+        None => Verdict::Missing,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let input = "# comment\n[day01]\nsilver = 7 # example\ngold = \"5\"\n\n[day12]\ngold=42\n";
+        let answers = Answers::parse(input).unwrap();
+        assert_eq!(answers.get(1, Part::Silver), Some("7"));
+        assert_eq!(answers.get(1, Part::Gold), Some("5"));
+        assert_eq!(answers.get(12, Part::Silver), None);
+        assert_eq!(answers.get(12, Part::Gold), Some("42"));
+
+        let answers = Answers::parse("[day01]\ngold = \"a#b\" # c#d\nsilver = \"#\"").unwrap();
+        assert_eq!(answers.get(1, Part::Gold), Some("a#b"));
+        assert_eq!(answers.get(1, Part::Silver), Some("#"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = Answers::parse("silver = 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:1:1: answer outside of a day table"
+        );
+        let error = Answers::parse("[day1]\nplatinum = 1").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:2:1: unknown part `platinum`");
+        let error = Answers::parse("[day1]\ngold = 1.5").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:8: `1.5` is not a number or a string"
+        );
+        assert!(Answers::parse("[dayone]").is_err());
+        assert!(Answers::parse("[day1").is_err());
+    }
+
+    #[test]
+    fn verdicts() {
+        let answers = Answers::parse("[day01]\nsilver = 7").unwrap();
+        assert_eq!(verdict(&answers, 1, Part::Silver, "7"), Verdict::Pass);
+        let expected = Verdict::Fail {
+            expected: String::from("7"),
+        };
+        assert_eq!(verdict(&answers, 1, Part::Silver, "8"), expected);
+        assert_eq!(verdict(&answers, 1, Part::Gold, "8"), Verdict::Missing);
+    }
+
+    #[test]
+    fn committed_answers_parse() {
+        let answers = Answers::from_file(&Answers::default_path()).unwrap();
+        assert_eq!(answers.get(4, Part::Gold), Some("23042"));
+    }
+}
@@ -0,0 +1,607 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use advent_of_code_2021::generate;
+use advent_of_code_2021::puzzle::Part;
+
+pub const USAGE: &str = "\
+usage: aoc [command]
+
+commands:
+    run <days> [options]    solve the selected days
+    verify [<days>] [--answers <path>]
+                            check the answers of the selected days, or all
+                            days, against answers.toml or <path>
+    new <day> <title>       generate src/puzzle/dayNN.rs from the template,
+                            an empty input file, and register the day
+    generate <day> [generate options]
+                            print a synthetic input for days 1 to 4
+    help                    print this message
+
+options:
+    -p, --part <part>       silver, gold or both (default)
+    -i, --input <path>      read the input of a single day from <path>,
+                            or from stdin when <path> is `-`
+    -f, --format <format>   print the answers as `text` (default), an
+                            aligned `table`, `json`, `csv` or a
+                            `markdown` table with a row per day
+    -t, --time[=<format>]   report how long parsing and each part took,
+                            as a `table` (default) after `text` or
+                            `table` answers, or as `json` instead of
+                            `text` answers or alongside `json` answers
+    -r, --report            print statistics of the depths after the day 1
+                            answers
+    -a, --anomalies         list spikes, outliers and reversals in the
+                            depths after the day 1 answers
+    -w, --watch             re-run the tests and the answers of a single
+                            day whenever its source or input changes
+
+generate options:
+    -s, --seed <n>          seed of the generated input (default 0)
+    -n, --size <n>          depths, instructions, diagnostics or boards
+                            to generate (default 1000)
+    --bits <n>              bits per diagnostic (default 12)
+    --board-size <n>        rows of a bingo board (default 5)
+    --max <n>               largest bingo number, at most 255 (default 99)
+
+<days> is `all`, a day such as `3`, a range such as `1..=4` or `1..5`,
+or a comma separated list of those such as `1,3..=4`.
+Running without a command is the same as `aoc run all`.
+
+Inputs are read from the directory in AOC_INPUT_DIR when it is set,
+and from the committed `inputs` directory otherwise.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Verify {
+        days: DaySelection,
+        answers: Option<PathBuf>,
+    },
+    New {
+        day: u8,
+        title: String,
+    },
+    Generate {
+        day: u8,
+        seed: u64,
+        options: generate::Options,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub days: DaySelection,
+    pub parts: Vec<Part>,
+    pub input: Input,
+    pub format: Format,
+    pub time: Option<TimeFormat>,
+    pub report: bool,
+    pub anomalies: bool,
+    pub watch: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            days: DaySelection::All,
+            parts: vec![Part::Silver, Part::Gold],
+            input: Input::Default,
+            format: Format::Text,
+            time: None,
+            report: false,
+            anomalies: false,
+            watch: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DaySelection {
+    All,
+    Some(Vec<DayRange>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayRange {
+    Single(u8),
+    Range(u8, u8),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    Default,
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl DaySelection {
+    /// Resolves the selection against the implemented days. Explicitly named
+    /// days must exist, ranges only pick up the days that do.
+    pub fn resolve(&self, available: &[u8]) -> Result<Vec<u8>, UsageError> {
+        let ranges = match self {
+            DaySelection::All => return Ok(available.to_vec()),
+            DaySelection::Some(ranges) => ranges,
+        };
+        let mut days = Vec::new();
+        for range in ranges {
+            match *range {
+                DayRange::Single(day) => {
+                    if !available.contains(&day) {
+                        return Err(UsageError(format!("day {} is not implemented", day)));
+                    }
+                    days.push(day);
+                }
+                DayRange::Range(first, last) => {
+                    let before = days.len();
+                    days.extend(available.iter().filter(|&&x| x >= first && x <= last));
+                    if days.len() == before {
+                        return Err(UsageError(format!(
+                            "no implemented days in {}..={}",
+                            first, last
+                        )));
+                    }
+                }
+            }
+        }
+        days.sort_unstable();
+        days.dedup();
+        Ok(days)
+    }
+}
+
+pub fn parse(args: &[String]) -> Result<Command, UsageError> {
+    let mut args = args.iter().map(String::as_str);
+    match args.next() {
+        None => Ok(Command::Run(RunOptions::default())),
+        Some("run") => parse_run(args),
+        Some("verify") => parse_verify(args),
+        Some("new") => parse_new(args),
+        Some("generate") => parse_generate(args),
+        Some("help" | "-h" | "--help") => Ok(Command::Help),
+        Some(other) => Err(UsageError(format!("unknown command `{}`", other))),
+    }
+}
+
+fn parse_run<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Command, UsageError> {
+    let mut days = None;
+    let mut options = RunOptions::default();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--part" | "-p" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| UsageError(String::from("--part needs a value")))?;
+                options.parts = parse_parts(value)?;
+            }
+            "--input" | "-i" => {
+                options.input = match args.next() {
+                    Some("-") => Input::Stdin,
+                    Some(path) => Input::File(PathBuf::from(path)),
+                    None => return Err(UsageError(String::from("--input needs a path"))),
+                };
+            }
+            "--format" | "-f" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| UsageError(String::from("--format needs a value")))?;
+                options.format = parse_format(value)?;
+            }
+            "--time" | "-t" => options.time = Some(TimeFormat::Table),
+            "--time=table" => options.time = Some(TimeFormat::Table),
+            "--time=json" => options.time = Some(TimeFormat::Json),
+            "--report" | "-r" => options.report = true,
+            "--anomalies" | "-a" => options.anomalies = true,
+            "--watch" | "-w" => options.watch = true,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("unknown option `{}`", arg)));
+            }
+            _ if days.is_none() => days = Some(parse_days(arg)?),
+            _ => return Err(UsageError(format!("unexpected argument `{}`", arg))),
+        }
+    }
+    options.days = days.ok_or_else(|| UsageError(String::from("run needs a day selection")))?;
+    check_output(options.format, options.time)?;
+    Ok(Command::Run(options))
+}
+
+/// Rejects answers and timings that cannot share stdout, such as a timing
+/// table after JSON, or where the timings would replace the answers, such as
+/// CSV with JSON timings.
+fn check_output(format: Format, time: Option<TimeFormat>) -> Result<(), UsageError> {
+    let time = match (format, time) {
+        (_, None)
+        | (Format::Text | Format::Table, Some(TimeFormat::Table))
+        | (Format::Text | Format::Json, Some(TimeFormat::Json)) => return Ok(()),
+        (_, Some(TimeFormat::Table)) => "table",
+        (_, Some(TimeFormat::Json)) => "json",
+    };
+    let format = match format {
+        Format::Text => "text",
+        Format::Table => "table",
+        Format::Json => "json",
+        Format::Csv => "csv",
+        Format::Markdown => "markdown",
+    };
+    Err(UsageError(format!(
+        "--format {} cannot be printed with --time={}",
+        format, time
+    )))
+}
+
+fn parse_verify<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Command, UsageError> {
+    let mut days = None;
+    let mut answers = None;
+    while let Some(arg) = args.next() {
+        match arg {
+            "--answers" | "-a" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| UsageError(String::from("--answers needs a path")))?;
+                answers = Some(PathBuf::from(path));
+            }
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("unknown option `{}`", arg)));
+            }
+            _ if days.is_none() => days = Some(parse_days(arg)?),
+            _ => return Err(UsageError(format!("unexpected argument `{}`", arg))),
+        }
+    }
+    let days = days.unwrap_or(DaySelection::All);
+    Ok(Command::Verify { days, answers })
+}
+
+fn parse_new<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Command, UsageError> {
+    let day = args
+        .next()
+        .ok_or_else(|| UsageError(String::from("new needs a day and a title")))?;
+    let day = parse_day(day)?;
+    let title = args.collect::<Vec<&str>>().join(" ");
+    if title.is_empty() {
+        return Err(UsageError(String::from("new needs a title")));
+    }
+    Ok(Command::New { day, title })
+}
+
+fn parse_generate<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut options = generate::Options::default();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--seed" | "-s" => seed = parse_number(arg, args.next())?,
+            "--size" | "-n" => options.size = parse_number(arg, args.next())?,
+            "--bits" => options.bits = parse_number(arg, args.next())?,
+            "--board-size" => options.board_size = parse_number(arg, args.next())?,
+            "--max" => options.max = parse_number(arg, args.next())?,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("unknown option `{}`", arg)));
+            }
+            _ if day.is_none() => day = Some(parse_day(arg)?),
+            _ => return Err(UsageError(format!("unexpected argument `{}`", arg))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError(String::from("generate needs a day")))?;
+    Ok(Command::Generate { day, seed, options })
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<&str>) -> Result<T, UsageError> {
+    let value = value.ok_or_else(|| UsageError(format!("{} needs a number", option)))?;
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("`{}` is not a valid value for {}", value, option)))
+}
+
+fn parse_parts(value: &str) -> Result<Vec<Part>, UsageError> {
+    match value {
+        "silver" | "1" => Ok(vec![Part::Silver]),
+        "gold" | "2" => Ok(vec![Part::Gold]),
+        "both" => Ok(vec![Part::Silver, Part::Gold]),
+        _ => Err(UsageError(format!("unknown part `{}`", value))),
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format, UsageError> {
+    match value {
+        "text" => Ok(Format::Text),
+        "table" => Ok(Format::Table),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        "markdown" | "md" => Ok(Format::Markdown),
+        _ => Err(UsageError(format!("unknown format `{}`", value))),
+    }
+}
+
+fn parse_days(value: &str) -> Result<DaySelection, UsageError> {
+    if value == "all" {
+        return Ok(DaySelection::All);
+    }
+    let ranges = value
+        .split(',')
+        .map(parse_day_range)
+        .collect::<Result<Vec<DayRange>, UsageError>>()?;
+    Ok(DaySelection::Some(ranges))
+}
+
+fn parse_day_range(value: &str) -> Result<DayRange, UsageError> {
+    if let Some((first, last)) = value.split_once("..=") {
+        let (first, last) = (parse_day(first)?, parse_day(last)?);
+        if first > last {
+            return Err(UsageError(format!("empty day range `{}`", value)));
+        }
+        return Ok(DayRange::Range(first, last));
+    }
+    if let Some((first, end)) = value.split_once("..") {
+        // The exclusive end may be one past the last day.
+        let end = match end.parse::<u8>() {
+            Ok(26) => 26,
+            _ => parse_day(end)?,
+        };
+        let first = parse_day(first)?;
+        if first >= end {
+            return Err(UsageError(format!("empty day range `{}`", value)));
+        }
+        return Ok(DayRange::Range(first, end - 1));
+    }
+    Ok(DayRange::Single(parse_day(value)?))
+}
+
+fn parse_day(value: &str) -> Result<u8, UsageError> {
+    match value.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(UsageError(format!(
+            "`{}` is not a day between 1 and 25",
+            value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn no_arguments_runs_everything() {
+        let expected = Command::Run(RunOptions::default());
+        assert_eq!(parse(&args("")), Ok(expected));
+    }
+
+    #[test]
+    fn run_single_day_and_part() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(3)]),
+            parts: vec![Part::Gold],
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 3 --part gold")), Ok(expected));
+    }
+
+    #[test]
+    fn run_ranges() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Range(1, 4), DayRange::Range(6, 7)]),
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 1..=4,6..8")), Ok(expected));
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Range(1, 25), DayRange::Range(25, 25)]),
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 1..26,25..26")), Ok(expected));
+        assert!(parse(&args("run 1..27")).is_err());
+        assert!(parse(&args("run 1..=26")).is_err());
+        assert!(parse(&args("run 26..26")).is_err());
+    }
+
+    #[test]
+    fn run_with_input() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(2)]),
+            input: Input::File(PathBuf::from("example.txt")),
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 2 --input example.txt")), Ok(expected));
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(2)]),
+            parts: vec![Part::Silver],
+            input: Input::Stdin,
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 2 -i - -p silver")), Ok(expected));
+        assert!(parse(&args("run 2 --input")).is_err());
+    }
+
+    #[test]
+    fn run_with_time() {
+        let expected = Command::Run(RunOptions {
+            time: Some(TimeFormat::Table),
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run all --time")), Ok(expected));
+        let expected = Command::Run(RunOptions {
+            time: Some(TimeFormat::Json),
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run all --time=json")), Ok(expected));
+        assert!(parse(&args("run all --time=xml")).is_err());
+    }
+
+    #[test]
+    fn run_with_format() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(1)]),
+            format: Format::Csv,
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 1 --format csv")), Ok(expected));
+        let expected = Command::Run(RunOptions {
+            format: Format::Markdown,
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run all -f md")), Ok(expected));
+        assert!(parse(&args("run all --format")).is_err());
+        assert!(parse(&args("run all --format xml")).is_err());
+    }
+
+    #[test]
+    fn format_with_time() {
+        let combinations = [
+            ("text", "table", true),
+            ("text", "json", true),
+            ("table", "table", true),
+            ("table", "json", false),
+            ("json", "table", false),
+            ("json", "json", true),
+            ("csv", "table", false),
+            ("csv", "json", false),
+            ("markdown", "table", false),
+            ("markdown", "json", false),
+        ];
+        for (format, time, valid) in combinations {
+            let line = format!("run all --format {} --time={}", format, time);
+            let result = parse(&args(&line));
+            if valid {
+                assert!(result.is_ok(), "{}", line);
+            } else {
+                let expected =
+                    format!("--format {} cannot be printed with --time={}", format, time);
+                assert_eq!(result, Err(UsageError(expected)), "{}", line);
+            }
+        }
+        for format in ["text", "table", "json", "csv", "markdown"] {
+            let line = format!("run all --format {}", format);
+            assert!(parse(&args(&line)).is_ok(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn run_with_watch() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(4)]),
+            watch: true,
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 4 --watch")), Ok(expected));
+    }
+
+    #[test]
+    fn run_with_report() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(1)]),
+            report: true,
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 1 --report")), Ok(expected));
+    }
+
+    #[test]
+    fn run_with_anomalies() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Some(vec![DayRange::Single(1)]),
+            report: true,
+            anomalies: true,
+            ..RunOptions::default()
+        });
+        assert_eq!(parse(&args("run 1 -a --report")), Ok(expected));
+    }
+
+    #[test]
+    fn verify() {
+        let expected = Command::Verify {
+            days: DaySelection::All,
+            answers: None,
+        };
+        assert_eq!(parse(&args("verify")), Ok(expected));
+        let expected = Command::Verify {
+            days: DaySelection::Some(vec![DayRange::Single(2)]),
+            answers: Some(PathBuf::from("mine.toml")),
+        };
+        assert_eq!(parse(&args("verify 2 --answers mine.toml")), Ok(expected));
+        assert!(parse(&args("verify --answers")).is_err());
+    }
+
+    #[test]
+    fn new() {
+        let expected = Command::New {
+            day: 5,
+            title: String::from("Hydrothermal Venture"),
+        };
+        assert_eq!(parse(&args("new 5 Hydrothermal Venture")), Ok(expected));
+        assert!(parse(&args("new 5")).is_err());
+        assert!(parse(&args("new five Venture")).is_err());
+    }
+
+    #[test]
+    fn generate() {
+        let expected = Command::Generate {
+            day: 3,
+            seed: 0,
+            options: generate::Options::default(),
+        };
+        assert_eq!(parse(&args("generate 3")), Ok(expected));
+        let expected = Command::Generate {
+            day: 4,
+            seed: 42,
+            options: generate::Options {
+                size: 10,
+                board_size: 4,
+                max: 50,
+                ..generate::Options::default()
+            },
+        };
+        let line = "generate 4 --seed 42 -n 10 --board-size 4 --max 50";
+        assert_eq!(parse(&args(line)), Ok(expected));
+        assert!(parse(&args("generate")).is_err());
+        assert!(parse(&args("generate 4 --max 256")).is_err());
+        assert!(parse(&args("generate 4 --seed")).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse(&args("fly")).is_err());
+        assert!(parse(&args("run")).is_err());
+        assert!(parse(&args("run 26")).is_err());
+        assert!(parse(&args("run 4..=1")).is_err());
+        assert!(parse(&args("run 1 --part platinum")).is_err());
+        assert!(parse(&args("run 1 2")).is_err());
+    }
+
+    #[test]
+    fn resolve_against_available() {
+        let available = [1, 2, 3, 4];
+        let selection = DaySelection::Some(vec![DayRange::Range(3, 10), DayRange::Single(1)]);
+        assert_eq!(selection.resolve(&available), Ok(vec![1, 3, 4]));
+        let selection = DaySelection::Some(vec![DayRange::Single(5)]);
+        assert!(selection.resolve(&available).is_err());
+        assert_eq!(DaySelection::All.resolve(&available), Ok(vec![1, 2, 3, 4]));
+    }
+}
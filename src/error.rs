@@ -0,0 +1,125 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// Malformed input text, `line` and `column` are 1-based.
+    Parse {
+        file: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Input that parses but does not describe a valid puzzle.
+    InvalidModel(String),
+    /// A puzzle that is valid but has no answer, such as a bingo nobody wins.
+    NoAnswer(String),
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            file: None,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Attaches the file the input was read from, if the error does not
+    /// already name one.
+    pub fn in_file(self, path: &Path) -> Self {
+        match self {
+            Error::Io { path: None, source } => Error::Io {
+                path: Some(path.to_owned()),
+                source,
+            },
+            Error::Parse {
+                file: None,
+                line,
+                column,
+                message,
+            } => Error::Parse {
+                file: Some(path.to_owned()),
+                line,
+                column,
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "{}: {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "{}", source),
+            Error::Parse {
+                file,
+                line,
+                column,
+                message,
+            } => {
+                let file = match file {
+                    Some(path) => path.display().to_string(),
+                    None => String::from("<input>"),
+                };
+                write!(f, "{}:{}:{}: {}", file, line, column, message)
+            }
+            Error::InvalidModel(message) => write!(f, "invalid input: {}", message),
+            Error::NoAnswer(message) => write!(f, "no answer: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_error_display() {
+        let error = Error::parse(3, 7, "unknown instruction `sideways`");
+        assert_eq!(
+            error.to_string(),
+            "<input>:3:7: unknown instruction `sideways`"
+        );
+        let error = error.in_file(Path::new("inputs/day02_dive.txt"));
+        assert_eq!(
+            error.to_string(),
+            "inputs/day02_dive.txt:3:7: unknown instruction `sideways`"
+        );
+    }
+
+    #[test]
+    fn in_file_keeps_existing_file() {
+        let error = Error::parse(1, 1, "bad").in_file(Path::new("a.txt"));
+        let error = error.in_file(Path::new("b.txt"));
+        assert_eq!(error.to_string(), "a.txt:1:1: bad");
+    }
+}
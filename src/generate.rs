@@ -0,0 +1,331 @@
+//! Synthetic puzzle inputs of any size. The same seed always produces the
+//! same input, and every generated input has an answer for both parts.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::error::{Error, Result};
+use crate::puzzle::day03::Decoder;
+
+/// Draws of diagnostics before giving up on finding a set with a life support
+/// rating.
+const DIAGNOSTIC_ATTEMPTS: usize = 1000;
+
+/// The shape of the generated input. `size` is the number of records: depths,
+/// instructions, diagnostics or bingo boards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub size: usize,
+    pub bits: usize,
+    pub board_size: usize,
+    pub max: u8,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            size: 1000,
+            bits: 12,
+            board_size: 5,
+            max: 99,
+        }
+    }
+}
+
+/// Input for `day` in the format of the puzzle.
+pub fn generate(day: u8, seed: u64, options: &Options) -> Result<String> {
+    let mut rng = Rng::new(seed);
+    match day {
+        1 => Ok(depths(&mut rng, options.size)),
+        2 => Ok(instructions(&mut rng, options.size)),
+        3 => diagnostics(&mut rng, options.size, options.bits),
+        4 => bingo(&mut rng, options.size, options.board_size, options.max),
+        _ => Err(Error::InvalidModel(format!(
+            "there is no generator for day {}",
+            day
+        ))),
+    }
+}
+
+/// SplitMix64, small and good enough to shuffle puzzle input.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..bound`, `bound` must not be zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        // Rejecting the top of the range keeps the result unbiased.
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for index in (1..values.len()).rev() {
+            let other = self.below(index as u64 + 1) as usize;
+            values.swap(index, other);
+        }
+    }
+}
+
+/// A sonar sweep drifting deeper, one depth per line.
+pub fn depths(rng: &mut Rng, count: usize) -> String {
+    let mut output = String::new();
+    let mut depth: u64 = 100 + rng.below(100);
+    for _ in 0..count {
+        writeln!(output, "{}", depth).unwrap();
+        // Steps of -20..=30 average a few meters deeper per reading.
+        depth = (depth + rng.below(51)).saturating_sub(20);
+    }
+    output
+}
+
+/// Course instructions whose aim never goes above the surface.
+pub fn instructions(rng: &mut Rng, count: usize) -> String {
+    let mut output = String::new();
+    let mut aim: u64 = 0;
+    for _ in 0..count {
+        let value = 1 + rng.below(9);
+        let direction = match rng.below(3) {
+            0 => "forward",
+            1 => {
+                aim += value;
+                "down"
+            }
+            _ if aim >= value => {
+                aim -= value;
+                "up"
+            }
+            _ => "forward",
+        };
+        writeln!(output, "{} {}", direction, value).unwrap();
+    }
+    output
+}
+
+/// `count` distinct diagnostics of `bits` bits whose oxygen generator and CO2
+/// scrubber filters both end at exactly one diagnostic. Being distinct is not
+/// enough: when every diagnostic left agrees on a bit, the CO2 filter keeps
+/// none of them. Sets like that are drawn again.
+pub fn diagnostics(rng: &mut Rng, count: usize, bits: usize) -> Result<String> {
+    // Any width day 3 reads multiplies within its u128 answers.
+    if !(1..usize::BITS as usize).contains(&bits) {
+        let message = format!("{} bit diagnostics are not supported", bits);
+        return Err(Error::InvalidModel(message));
+    }
+    let space = 1u64 << bits.min(63);
+    if count == 0 || count as u64 > space {
+        let message = format!("cannot pick {} distinct {} bit diagnostics", count, bits);
+        return Err(Error::InvalidModel(message));
+    }
+
+    for _ in 0..DIAGNOSTIC_ATTEMPTS {
+        let values = distinct(rng, count, space);
+        let mut decoder = Decoder::new(bits);
+        decoder.decode(values.iter().map(|&value| value as usize).collect());
+        if decoder.life_support_rating().is_err() {
+            continue;
+        }
+
+        let mut output = String::new();
+        for value in values {
+            writeln!(output, "{:0width$b}", value, width = bits).unwrap();
+        }
+        return Ok(output);
+    }
+    let message = format!(
+        "found no {} distinct {} bit diagnostics with a life support rating",
+        count, bits
+    );
+    Err(Error::InvalidModel(message))
+}
+
+/// `count` distinct values in `0..space`.
+fn distinct(rng: &mut Rng, count: usize, space: u64) -> Vec<u64> {
+    if count as u64 > space / 2 {
+        let mut values: Vec<u64> = (0..space).collect();
+        rng.shuffle(&mut values);
+        values.truncate(count);
+        values
+    } else {
+        let mut seen = HashSet::with_capacity(count);
+        let mut values = Vec::with_capacity(count);
+        while values.len() < count {
+            let value = rng.below(space);
+            if seen.insert(value) {
+                values.push(value);
+            }
+        }
+        values
+    }
+}
+
+/// A bingo game of `boards` square boards of `board_size` rows, drawing every
+/// number in `0..=max` so that every board wins.
+pub fn bingo(rng: &mut Rng, boards: usize, board_size: usize, max: u8) -> Result<String> {
+    let cells = board_size * board_size;
+    if board_size < 2 || cells > max as usize + 1 {
+        let message = format!(
+            "{0}x{0} boards need {1} distinct numbers, 0..={2} has {3}",
+            board_size,
+            cells,
+            max,
+            max as usize + 1
+        );
+        return Err(Error::InvalidModel(message));
+    }
+    if boards == 0 {
+        return Err(Error::InvalidModel(String::from("a game needs a board")));
+    }
+
+    let mut numbers: Vec<u8> = (0..=max).collect();
+    rng.shuffle(&mut numbers);
+    let draw_numbers: Vec<String> = numbers.iter().map(u8::to_string).collect();
+    let mut output = draw_numbers.join(",");
+    output.push('\n');
+
+    let width = max.to_string().len();
+    for _ in 0..boards {
+        rng.shuffle(&mut numbers);
+        output.push('\n');
+        for row in numbers[..cells].chunks(board_size) {
+            let row: Vec<String> = row
+                .iter()
+                .map(|number| format!("{:>width$}", number, width = width))
+                .collect();
+            writeln!(output, "{}", row.join(" ")).unwrap();
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::puzzle::day03::{bit_counts_reader, BinaryDiagnostic};
+    use crate::puzzle::day04::GiantSquid;
+    use crate::puzzle::{self, Part, Solution};
+
+    fn solve(day: u8, input: &str) -> Vec<String> {
+        let day = puzzle::find(day).unwrap();
+        (day.solve)(input, &[Part::Silver, Part::Gold])
+            .unwrap()
+            .answers
+            .into_iter()
+            .map(|answer| answer.value)
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_input() {
+        let options = Options::default();
+        for day in 1..=4 {
+            let first = generate(day, 7, &options).unwrap();
+            assert_eq!(first, generate(day, 7, &options).unwrap());
+            assert_ne!(first, generate(day, 8, &options).unwrap());
+        }
+    }
+
+    #[test]
+    fn every_day_solves() {
+        for seed in 0..20 {
+            let options = Options {
+                size: 1 + seed as usize * 7,
+                bits: 8 + seed as usize % 10,
+                board_size: 2 + seed as usize % 4,
+                max: 24 + seed as u8,
+            };
+            for day in 1..=4 {
+                let input = generate(day, seed, &options).unwrap();
+                let answers = solve(day, &input);
+                assert_eq!(answers.len(), 2, "day {} seed {}", day, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn diagnostics_have_a_life_support_rating() {
+        // Seeds 4, 8 and 9 first draw 3 diagnostics that leave the CO2
+        // filter empty, such as 0110, 0001 and 0100 for seed 8.
+        let small = Options {
+            size: 3,
+            bits: 4,
+            ..Options::default()
+        };
+        for (seed, options) in (0..50).flat_map(|seed| [(seed, small), (seed, Options::default())])
+        {
+            let input = generate(3, seed, &options).unwrap();
+            assert_ne!(input, "0110\n0001\n0100\n");
+            let report = BinaryDiagnostic::parse(&input).unwrap();
+            assert!(BinaryDiagnostic::silver(&report).is_ok(), "seed {}", seed);
+            assert!(BinaryDiagnostic::gold(&report).is_ok(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn wide_diagnostics_solve() {
+        for bits in [33, 48, 63] {
+            let mut rng = Rng::new(bits as u64);
+            let input = diagnostics(&mut rng, 50, bits).unwrap();
+            let report = BinaryDiagnostic::parse(&input).unwrap();
+            let counts = bit_counts_reader(input.as_bytes()).unwrap();
+            let silver = BinaryDiagnostic::silver(&report).unwrap();
+            assert_eq!(silver, counts.power_consumption(), "{} bits", bits);
+            assert!(BinaryDiagnostic::gold(&report).is_ok(), "{} bits", bits);
+        }
+    }
+
+    #[test]
+    fn shapes() {
+        let mut rng = Rng::new(1);
+        assert_eq!(depths(&mut rng, 10).lines().count(), 10);
+        let input = diagnostics(&mut rng, 8, 3).unwrap();
+        let mut values: Vec<&str> = input.lines().collect();
+        values.sort_unstable();
+        assert_eq!(
+            values,
+            ["000", "001", "010", "011", "100", "101", "110", "111"]
+        );
+
+        let input = bingo(&mut rng, 3, 5, 99).unwrap();
+        let game = GiantSquid::parse(&input).unwrap();
+        assert_eq!(game.draw_numbers.len(), 100);
+        assert_eq!(game.boards.len(), 3);
+    }
+
+    #[test]
+    fn rejects_impossible_shapes() {
+        let mut rng = Rng::new(1);
+        assert!(diagnostics(&mut rng, 9, 3).is_err());
+        assert!(diagnostics(&mut rng, 1, 0).is_err());
+        assert!(diagnostics(&mut rng, 1, 64).is_err());
+        assert!(bingo(&mut rng, 1, 5, 23).is_err());
+        assert!(bingo(&mut rng, 1, 1, 99).is_err());
+        assert!(bingo(&mut rng, 0, 5, 99).is_err());
+        assert!(generate(5, 0, &Options::default()).is_err());
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut rng = Rng::new(3);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+    }
+}
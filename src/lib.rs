@@ -0,0 +1,11 @@
+//! Advent of Code 2021 solutions as a library. Every day lives in
+//! [`puzzle`] with its model types public, so the solvers can be used outside
+//! the `aoc` runner; [`util`] holds the input parsers they are built on.
+
+pub mod answers;
+pub mod error;
+pub mod generate;
+pub mod puzzle;
+pub mod report;
+pub mod sonar;
+pub mod util;
@@ -1,9 +1,252 @@
-mod puzzle;
-mod util;
-
-fn main() {
-    puzzle::day01::solve();
-    puzzle::day02::solve();
-    puzzle::day03::solve();
-    puzzle::day04::solve();
+mod cli;
+mod scaffold;
+mod watch;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use advent_of_code_2021::answers::{self, Answers, Verdict};
+use advent_of_code_2021::error::{Error, Result};
+use advent_of_code_2021::puzzle::day01::SonarSweep;
+use advent_of_code_2021::puzzle::{self, Answer, Day, Part, Solution, Solved, Timings};
+use advent_of_code_2021::sonar::anomaly::{Anomaly, Detector};
+use advent_of_code_2021::sonar::{self, SonarReport};
+use advent_of_code_2021::{generate, report, util};
+use cli::{Command, DaySelection, Format, Input, RunOptions, TimeFormat, UsageError};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(error) => return usage_error(error),
+    };
+
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Command::Run(options) => {
+            let days = match resolve_days(&options.days) {
+                Ok(days) => days,
+                Err(error) => return usage_error(error),
+            };
+            if options.input != Input::Default && days.len() != 1 {
+                let error = UsageError(String::from("--input needs exactly one day"));
+                return usage_error(error);
+            }
+            for (flag, set) in [
+                ("--report", options.report),
+                ("--anomalies", options.anomalies),
+            ] {
+                if set && !days.contains(&1) {
+                    return usage_error(UsageError(format!("{} needs day 1", flag)));
+                }
+                if set && (options.format != Format::Text || options.time.is_some()) {
+                    return usage_error(UsageError(format!("{} prints text only", flag)));
+                }
+            }
+            if options.watch {
+                if days.len() != 1 || options.input == Input::Stdin {
+                    let error = UsageError(String::from("--watch needs one day and an input file"));
+                    return usage_error(error);
+                }
+                let day = puzzle::find(days[0]).unwrap();
+                return watch::watch(day, &options.input, &options.parts);
+            }
+            run(&days, &options)
+        }
+        Command::New { day, title } => {
+            let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            match scaffold::new_day(&root, day, &title) {
+                Ok(written) => {
+                    for path in written {
+                        println!("wrote {}", path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(error) => {
+                    eprintln!("error: {}", error);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::Generate { day, seed, options } => match generate::generate(day, seed, &options) {
+            Ok(input) => {
+                print!("{}", input);
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Command::Verify { days, answers } => {
+            let days = match resolve_days(&days) {
+                Ok(days) => days,
+                Err(error) => return usage_error(error),
+            };
+            verify(&days, answers)
+        }
+    }
+}
+
+fn resolve_days(selection: &DaySelection) -> std::result::Result<Vec<u8>, UsageError> {
+    let available: Vec<u8> = puzzle::DAYS.iter().map(|day| day.number).collect();
+    selection.resolve(&available)
+}
+
+fn verify(days: &[u8], answers: Option<PathBuf>) -> ExitCode {
+    let path = answers.unwrap_or_else(Answers::default_path);
+    let answers = match Answers::from_file(&path) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for day in days.iter().filter_map(|&number| puzzle::find(number)) {
+        let solved = match run_day(day, &[Part::Silver, Part::Gold], &Input::Default) {
+            Ok(solved) => solved,
+            Err(error) => {
+                eprintln!("error: day {}: {}", day.number, error);
+                failed += 1;
+                continue;
+            }
+        };
+        for answer in &solved.answers {
+            let verdict = answers::verdict(&answers, answer.day, answer.part, &answer.value);
+            match verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { .. } => failed += 1,
+                Verdict::Missing => missing += 1,
+            }
+            report::print_verdict(answer, &verdict);
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} missing",
+        passed, failed, missing
+    );
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run(days: &[u8], options: &RunOptions) -> ExitCode {
+    let mut failed = false;
+    let mut answers: Vec<(&Day, Vec<Answer>)> = Vec::new();
+    let mut timings: Vec<(&Day, Timings)> = Vec::new();
+    for day in days.iter().filter_map(|&number| puzzle::find(number)) {
+        let result = read_input(day, &options.input).and_then(|(input, path)| {
+            let solved = solve_day(day, &input, &options.parts, &path)?;
+            if options.time != Some(TimeFormat::Json) && options.format == Format::Text {
+                report::print_day(day, &solved.answers);
+            }
+            if options.report && day.number == 1 {
+                print!(
+                    "\n{}",
+                    sonar_report(&input).map_err(|error| in_file(error, &path))?
+                );
+            }
+            if options.anomalies && day.number == 1 {
+                let depths = SonarSweep::parse(&input).map_err(|error| in_file(error, &path))?;
+                print_anomalies(&Detector::default().detect(&depths));
+            }
+            Ok(solved)
+        });
+        match result {
+            Ok(solved) => {
+                answers.push((day, solved.answers));
+                timings.push((day, solved.timings));
+            }
+            Err(error) => {
+                eprintln!("error: day {}: {}", day.number, error);
+                failed = true;
+            }
+        }
+    }
+
+    match (options.format, options.time) {
+        (Format::Json, Some(TimeFormat::Json)) => {
+            print!("{}", report::run_json(&answers, &timings));
+        }
+        (Format::Text, Some(TimeFormat::Json)) => {
+            print!("{}", report::timings_json(&timings));
+        }
+        (format, time) => {
+            match format {
+                Format::Text => {}
+                Format::Table => print!("{}", report::answers_table(&answers)),
+                Format::Json => print!("{}", report::answers_json(&answers)),
+                Format::Csv => print!("{}", report::answers_csv(&answers)),
+                Format::Markdown => print!("{}", report::answers_markdown(&answers)),
+            }
+            if time == Some(TimeFormat::Table) {
+                println!();
+                report::print_timings(&timings);
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_day(day: &Day, parts: &[Part], input: &Input) -> Result<Solved> {
+    let (input, path) = read_input(day, input)?;
+    solve_day(day, &input, parts, &path)
+}
+
+/// The input text of `day`, and the file it was read from unless that was
+/// stdin.
+fn read_input(day: &Day, input: &Input) -> Result<(String, Option<PathBuf>)> {
+    match input {
+        Input::Default => {
+            let path = day.input_path();
+            Ok((util::file2str(&path)?, Some(path)))
+        }
+        Input::File(path) => Ok((util::file2str(path)?, Some(path.clone()))),
+        Input::Stdin => Ok((util::stdin2str()?, None)),
+    }
+}
+
+fn solve_day(day: &Day, input: &str, parts: &[Part], path: &Option<PathBuf>) -> Result<Solved> {
+    (day.solve)(input, parts).map_err(|error| in_file(error, path))
+}
+
+fn in_file(error: Error, path: &Option<PathBuf>) -> Error {
+    match path {
+        Some(path) => error.in_file(path),
+        None => error,
+    }
+}
+
+fn sonar_report(input: &str) -> Result<SonarReport> {
+    let depths = SonarSweep::parse(input)?;
+    SonarReport::new(&depths, sonar::report::DEFAULT_BUCKETS)
+}
+
+fn print_anomalies(anomalies: &[Anomaly]) {
+    println!("\nanomalies     {}", anomalies.len());
+    if !anomalies.is_empty() {
+        println!("{:>6}  {:>6}  reason", "index", "depth");
+    }
+    for anomaly in anomalies {
+        println!("{}", anomaly);
+    }
+}
+
+fn usage_error(error: UsageError) -> ExitCode {
+    eprintln!("error: {}", error);
+    eprintln!("{}", cli::USAGE);
+    ExitCode::from(2)
 }
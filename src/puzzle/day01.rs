@@ -1,64 +1,183 @@
-use crate::util::str2vec_numbers;
+use std::any::type_name;
+use std::io::BufRead;
 
-fn input() -> Vec<usize> {
-    let input_string = crate::util::file2str("inputs/day01_sonar_sweep.txt");
-    str2vec_numbers(&input_string)
+use crate::error::{Error, Result};
+use crate::puzzle::Solution;
+use crate::sonar::Depth;
+use crate::util::{for_each_line, str2vec_numbers_strict, Integer};
+
+pub struct SonarSweep;
+
+impl Solution for SonarSweep {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Sonar Sweep";
+    const INPUT: &'static str = "day01_sonar_sweep.txt";
+    const SILVER_LABEL: &'static str = "increases";
+    const GOLD_LABEL: &'static str = "increases";
+
+    type Model = Vec<usize>;
+    type Silver = usize;
+    type Gold = usize;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        str2vec_numbers_strict(input)
+    }
+
+    fn silver(depths: &Self::Model) -> Result<Self::Silver> {
+        Ok(sonar_sweep(depths))
+    }
+
+    fn gold(depths: &Self::Model) -> Result<Self::Gold> {
+        Ok(sonar_sweep_sliding_sum(depths))
+    }
 }
 
-pub fn solve() {
-    println!("--- Day 1: Sonar Sweep ---");
-    let input = input();
+/// Depths can be any integer type, `usize` for the puzzle or `u128` for
+/// synthetic sweeps deeper than that. Counting only compares depths, so it
+/// cannot overflow.
+pub fn sonar_sweep<D: Ord>(depths: &[D]) -> usize {
+    sonar_sweep_window(depths, 1)
+}
 
-    let increases = sonar_sweep(&input);
-    println!("\tsilver - increases {}", increases);
+/// [`sonar_sweep`] over any series of depths, in a single pass.
+pub fn count_increases<D: Copy + Ord + Default>(depths: impl IntoIterator<Item = D>) -> usize {
+    depths
+        .into_iter()
+        .fold(IncreaseCounter::default(), IncreaseCounter::push)
+        .increases
+}
 
-    let increases = sonar_sweep_sliding_sum(&input);
-    println!("\tgold   - increases {}", increases);
+/// Counts the increases of both parts one depth at a time, remembering only
+/// the last three depths. Neighbouring windows share two depths, so comparing
+/// their sums is comparing the depth entering with the depth leaving.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IncreaseCounter<D = usize> {
+    pub increases: usize,
+    pub sliding_increases: usize,
+    recent: [D; 3],
+    seen: usize,
 }
 
-fn sonar_sweep(depths: &[usize]) -> usize {
-    if depths.len() == 0 {
-        return 0;
-    }
-    let mut previous = depths.first().unwrap();
-    let mut increases = 0;
-    for depth in depths {
-        if depth > previous {
-            increases += 1;
+impl<D: Copy + Ord> IncreaseCounter<D> {
+    pub fn push(mut self, depth: D) -> Self {
+        if self.seen >= 1 && depth > self.recent[(self.seen - 1) % 3] {
+            self.increases += 1;
         }
-        previous = depth;
+        if self.seen >= 3 && depth > self.recent[self.seen % 3] {
+            self.sliding_increases += 1;
+        }
+        self.recent[self.seen % 3] = depth;
+        self.seen += 1;
+        self
+    }
+}
+
+impl<D: Depth> IncreaseCounter<D> {
+    /// Counts the depths of a reader, one per line, read as `D`. A depth too
+    /// large for `D` is a parse error.
+    pub fn read<R: BufRead>(reader: R) -> Result<Self> {
+        let mut counter = Self::default();
+        for_each_line(reader, |line| {
+            let text = line.text.trim();
+            if !text.is_empty() {
+                counter = counter.push(line.parse(text)?);
+            }
+            Ok(())
+        })?;
+        Ok(counter)
     }
-    increases
 }
 
-fn sonar_sweep_sliding_sum(depths: &[usize]) -> usize {
-    const WINDOW_SIZE: usize = 3;
-    if depths.len() < 4 {
+/// Both parts straight from a reader, one depth per line, without keeping the
+/// depths in memory. [`IncreaseCounter::read`] reads other depth types.
+pub fn sonar_sweep_reader<R: BufRead>(reader: R) -> Result<IncreaseCounter> {
+    IncreaseCounter::read(reader)
+}
+
+pub fn sonar_sweep_sliding_sum<D: Ord>(depths: &[D]) -> usize {
+    sonar_sweep_window(depths, 3)
+}
+
+/// Increases between the sums of neighbouring windows of `k` depths. The
+/// windows share all but their first and last depth, so only those are
+/// compared and the sums are never computed. `k = 1` is [`sonar_sweep`].
+pub fn sonar_sweep_window<D: Ord>(depths: &[D], k: usize) -> usize {
+    if k == 0 || depths.len() <= k {
         return 0;
     }
-    let mut previous = slice_sum(&depths[..WINDOW_SIZE]);
-    let mut increases = 0;
-    for index in 0..=depths.len() - WINDOW_SIZE {
-        let depth_sum = slice_sum(&depths[index..index + WINDOW_SIZE]);
-        if depth_sum > previous {
-            increases += 1;
+    depths
+        .iter()
+        .zip(&depths[k..])
+        .filter(|(leaving, entering)| entering > leaving)
+        .count()
+}
+
+/// The sums of every window of `k` depths, for when the sums themselves are
+/// needed. A sum that does not fit in `D` is an error rather than a wrap, but
+/// one that only leaves `D` part way through, such as `i32::MAX + 1 - 1`,
+/// is not.
+pub fn window_sums<D: Integer>(depths: &[D], k: usize) -> Result<Vec<D>> {
+    if k == 0 || depths.len() < k {
+        return Ok(Vec::new());
+    }
+    let overflow = |start: usize| {
+        Error::InvalidModel(format!(
+            "the sum of depths {}..{} overflows {}",
+            start,
+            start + k,
+            type_name::<D>()
+        ))
+    };
+
+    // The sum wraps freely and counts its wraps, up past the maximum or down
+    // past the minimum. The true sum is in `D` exactly when they cancel out.
+    let (first, rest) = depths[..k].split_first().unwrap();
+    let mut sum = rest
+        .iter()
+        .fold((*first, 0), |sum, &depth| wrapping_add(sum, depth));
+    if sum.1 != 0 {
+        return Err(overflow(0));
+    }
+    let mut sums = Vec::with_capacity(depths.len() - k + 1);
+    sums.push(sum.0);
+    for (start, (&leaving, &entering)) in depths.iter().zip(&depths[k..]).enumerate() {
+        sum = wrapping_add(wrapping_sub(sum, leaving), entering);
+        if sum.1 != 0 {
+            return Err(overflow(start + 1));
         }
-        previous = depth_sum;
+        sums.push(sum.0);
     }
-    increases
+    Ok(sums)
 }
 
-fn slice_sum(window: &[usize]) -> usize {
-    window.iter().sum::<usize>()
+/// Adds to a wrapped sum. A wrap up past the maximum lands below the sum.
+fn wrapping_add<D: Integer>((sum, wraps): (D, isize), depth: D) -> (D, isize) {
+    match sum.overflowing_add(depth) {
+        (next, false) => (next, wraps),
+        (next, true) if next < sum => (next, wraps + 1),
+        (next, true) => (next, wraps - 1),
+    }
+}
+
+/// Subtracts from a wrapped sum. A wrap down past the minimum lands above it.
+fn wrapping_sub<D: Integer>((sum, wraps): (D, isize), depth: D) -> (D, isize) {
+    match sum.overflowing_sub(depth) {
+        (next, false) => (next, wraps),
+        (next, true) if next > sum => (next, wraps - 1),
+        (next, true) => (next, wraps + 1),
+    }
 }
 
 #[cfg(test)]
 mod sonar_sweep_test {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn given_none_return_zero() {
-        assert_eq!(sonar_sweep(&[]), 0);
+        assert_eq!(sonar_sweep::<usize>(&[]), 0);
     }
 
     #[test]
@@ -96,4 +215,151 @@ mod sonar_sweep_test {
             5
         );
     }
+
+    #[test]
+    fn window_example_test() {
+        let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert_eq!(sonar_sweep_window(&depths, 1), 7);
+        assert_eq!(sonar_sweep_window(&depths, 3), 5);
+        assert_eq!(sonar_sweep_window(&depths, 9), 1);
+        assert_eq!(sonar_sweep_window(&depths, 10), 0);
+        assert_eq!(sonar_sweep_window(&depths, 0), 0);
+    }
+
+    #[test]
+    fn reader_example_test() {
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        let counter = sonar_sweep_reader(input.as_bytes()).unwrap();
+        assert_eq!(counter.increases, 7);
+        assert_eq!(counter.sliding_increases, 5);
+    }
+
+    #[test]
+    fn reader_errors() {
+        let error = sonar_sweep_reader("1\n\n 2x\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:3:2: `2x` is not a valid usize");
+        let error = IncreaseCounter::<u32>::read("1\n4294967296\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:1: `4294967296` is not a valid u32"
+        );
+    }
+
+    #[test]
+    fn wide_depths() {
+        let input = format!("{}\n{}\n1\n{}\n", u128::MAX - 2, u128::MAX - 1, u128::MAX);
+        let counter = IncreaseCounter::<u128>::read(input.as_bytes()).unwrap();
+        assert_eq!(counter.increases, 2);
+        assert_eq!(counter.sliding_increases, 1);
+
+        let depths = [u64::MAX - 2, u64::MAX - 1, 1, u64::MAX];
+        assert_eq!(sonar_sweep(&depths), 2);
+        assert_eq!(sonar_sweep_sliding_sum(&depths), 1);
+    }
+
+    #[test]
+    fn window_sums_example_test() {
+        let depths = [199u32, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let sums = window_sums(&depths, 3).unwrap();
+        assert_eq!(sums, [607, 618, 618, 617, 647, 716, 769, 792]);
+        assert_eq!(sonar_sweep(&sums), sonar_sweep_sliding_sum(&depths));
+        assert_eq!(window_sums(&depths, 10).unwrap(), [2256]);
+        assert!(window_sums(&depths, 11).unwrap().is_empty());
+        assert!(window_sums(&depths, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn window_sums_overflow() {
+        let depths = [1, u32::MAX - 1, 2, 5];
+        let error = window_sums(&depths, 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid input: the sum of depths 1..3 overflows u32"
+        );
+        assert_eq!(
+            window_sums(&[u32::MAX, 1], 2).unwrap_err().to_string(),
+            "invalid input: the sum of depths 0..2 overflows u32"
+        );
+        assert_eq!(window_sums(&depths, 1).unwrap(), depths);
+        let wide: Vec<u64> = depths.iter().map(|&depth| depth as u64).collect();
+        assert_eq!(window_sums(&wide, 2).unwrap()[1], u32::MAX as u64 + 1);
+    }
+
+    #[test]
+    fn signed_window_sums() {
+        assert_eq!(window_sums(&[i32::MAX, 1, -1], 3).unwrap(), [i32::MAX]);
+        assert_eq!(
+            window_sums(&[i32::MIN, -1, 1, 1], 3).unwrap(),
+            [i32::MIN, 1]
+        );
+        assert_eq!(
+            window_sums(&[-2, i8::MAX, 1, 2], 2)
+                .unwrap_err()
+                .to_string(),
+            "invalid input: the sum of depths 1..3 overflows i8"
+        );
+        assert!(window_sums(&[i8::MIN, -1], 2).is_err());
+        assert_eq!(
+            window_sums(&[5, -3, i64::MAX, -9], 2).unwrap()[1],
+            i64::MAX - 3
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn counter_matches_slices(depths in vec(0usize..10_000, 0..200)) {
+            let counter = depths.iter().fold(IncreaseCounter::default(), |counter, &depth| {
+                counter.push(depth)
+            });
+            prop_assert_eq!(counter.increases, sonar_sweep(&depths));
+            prop_assert_eq!(count_increases(depths.iter().copied()), sonar_sweep(&depths));
+            prop_assert_eq!(counter.sliding_increases, sonar_sweep_sliding_sum(&depths));
+        }
+
+        #[test]
+        fn window_of_one_is_sonar_sweep(depths in vec(0usize..10_000, 0..200)) {
+            prop_assert_eq!(sonar_sweep_window(&depths, 1), sonar_sweep(&depths));
+        }
+
+        #[test]
+        fn window_matches_summed_windows(depths in vec(0usize..10_000, 0..100), k in 1usize..10) {
+            let sums: Vec<usize> = depths.windows(k).map(|window| window.iter().sum()).collect();
+            prop_assert_eq!(sonar_sweep_window(&depths, k), sonar_sweep(&sums));
+            prop_assert_eq!(window_sums(&depths, k).unwrap(), sums);
+        }
+
+        #[test]
+        fn window_sums_fail_exactly_on_overflow(depths in vec(0u32..u32::MAX, 0..50), k in 1usize..5) {
+            let wide: Vec<Vec<u64>> = depths
+                .windows(k)
+                .map(|window| window.iter().map(|&depth| depth as u64).collect())
+                .collect();
+            let fits = wide.iter().all(|window| window.iter().sum::<u64>() <= u32::MAX as u64);
+            prop_assert_eq!(window_sums(&depths, k).is_ok(), fits);
+        }
+
+        #[test]
+        fn signed_window_sums_fail_exactly_on_overflow(depths in vec(any::<i8>(), 0..50), k in 1usize..8) {
+            let wide: Vec<i64> = depths
+                .windows(k)
+                .map(|window| window.iter().map(|&depth| depth as i64).sum())
+                .collect();
+            match window_sums(&depths, k) {
+                Ok(sums) => prop_assert_eq!(sums.iter().map(|&sum| sum as i64).collect::<Vec<_>>(), wide),
+                Err(_) => prop_assert!(wide.iter().any(|&sum| i8::try_from(sum).is_err())),
+            }
+        }
+
+        #[test]
+        fn counts_pairwise_increases(depths in vec(0usize..10_000, 0..200)) {
+            let increases = depths.windows(2).filter(|pair| pair[1] > pair[0]).count();
+            prop_assert_eq!(sonar_sweep(&depths), increases);
+        }
+
+        #[test]
+        fn sliding_sum_is_bounded_by_windows(depths in vec(0usize..10_000, 0..200)) {
+            let increases = sonar_sweep_sliding_sum(&depths);
+            prop_assert!(increases <= depths.len().saturating_sub(3));
+        }
+    }
 }
@@ -1,38 +1,63 @@
-pub fn solve() {
-    println!("--- Day 2: Dive! ---");
-    let instructions = input();
-    let mut sub = Submarine::new();
-    for instruction in instructions.clone() {
-        sub.execute(instruction);
+use std::io::BufRead;
+
+use crate::error::{Error, Result};
+use crate::puzzle::Solution;
+use crate::util::{for_each_line, keyword_value, lines, Line};
+
+pub struct Dive;
+
+impl Solution for Dive {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Dive!";
+    const INPUT: &'static str = "day02_dive.txt";
+    const SILVER_LABEL: &'static str = "horizontal position";
+    const GOLD_LABEL: &'static str = "horizontal position";
+
+    type Model = Vec<Instruction>;
+    type Silver = u128;
+    type Gold = u128;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        let mut instructions: Vec<Instruction> = Vec::new();
+        for line in lines(input) {
+            if let Some(instruction) = line2instruction(line)? {
+                instructions.push(instruction);
+            }
+        }
+        Ok(instructions)
     }
 
-    println!(
-        "\tsilver - horizontal position {}",
+    fn silver(instructions: &Self::Model) -> Result<Self::Silver> {
+        let mut sub = Submarine::new();
+        for instruction in instructions {
+            sub.execute(*instruction)?;
+        }
         sub.horizontal_position()
-    );
-
-    let mut sub = Submarine::new();
-    for instruction in instructions {
-        sub.execute_with_aim(instruction);
     }
-    println!(
-        "\tgold   - horizontal position {}",
+
+    fn gold(instructions: &Self::Model) -> Result<Self::Gold> {
+        let mut sub = Submarine::new();
+        for instruction in instructions {
+            sub.execute_with_aim(*instruction)?;
+        }
         sub.horizontal_position()
-    );
+    }
 }
 
-#[derive(Clone, Copy)]
-enum Instruction {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Instruction {
     Down(usize),
     Up(usize),
     Forward(usize),
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Submarine {
-    x: isize,
-    z: isize,
-    aim: isize,
+/// Positions are `i128`, wide enough for the aimed depth of billions of
+/// generated instructions. A course that leaves even that range is an error.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Submarine {
+    x: i128,
+    z: i128,
+    aim: i128,
 }
 
 impl Submarine {
@@ -40,56 +65,75 @@ impl Submarine {
         Self { x: 0, z: 0, aim: 0 }
     }
 
-    pub fn execute(&mut self, instruction: Instruction) {
+    pub fn execute(&mut self, instruction: Instruction) -> Result<()> {
         match instruction {
-            Instruction::Down(value) => self.z -= value as isize,
-            Instruction::Up(value) => self.z += value as isize,
-            Instruction::Forward(value) => self.x += value as isize,
+            Instruction::Down(value) => self.z = checked(self.z.checked_sub(value as i128))?,
+            Instruction::Up(value) => self.z = checked(self.z.checked_add(value as i128))?,
+            Instruction::Forward(value) => self.x = checked(self.x.checked_add(value as i128))?,
         }
+        Ok(())
     }
 
-    pub fn execute_with_aim(&mut self, instruction: Instruction) {
+    pub fn execute_with_aim(&mut self, instruction: Instruction) -> Result<()> {
         match instruction {
-            Instruction::Down(value) => self.aim += value as isize,
-            Instruction::Up(value) => self.aim -= value as isize,
+            Instruction::Down(value) => self.aim = checked(self.aim.checked_add(value as i128))?,
+            Instruction::Up(value) => self.aim = checked(self.aim.checked_sub(value as i128))?,
             Instruction::Forward(value) => {
-                self.x += value as isize;
-                self.z -= value as isize * self.aim;
+                let value = value as i128;
+                self.x = checked(self.x.checked_add(value))?;
+                let dive = value.checked_mul(self.aim);
+                self.z = checked(dive.and_then(|dive| self.z.checked_sub(dive)))?;
             }
         }
+        Ok(())
     }
 
-    pub fn horizontal_position(&self) -> usize {
-        (self.x * self.z).abs() as usize
+    pub fn horizontal_position(&self) -> Result<u128> {
+        checked(self.x.checked_mul(self.z)).map(i128::unsigned_abs)
     }
 }
 
-fn input() -> Vec<Instruction> {
-    let input_string = crate::util::file2str("inputs/day02_dive.txt");
-    let mut instructions: Vec<Instruction> = Vec::new();
-    for line in input_string.lines() {
-        if let Some(instruction) = line2instruction(line) {
-            instructions.push(instruction);
+fn checked(value: Option<i128>) -> Result<i128> {
+    value.ok_or_else(|| Error::NoAnswer(String::from("the course of the submarine overflows i128")))
+}
+
+/// Steers a submarine for each part as the instructions are read, without
+/// keeping them in memory. The first submarine ignores the aim.
+pub fn dive_reader<R: BufRead>(reader: R) -> Result<(Submarine, Submarine)> {
+    let mut sub = Submarine::new();
+    let mut aimed = Submarine::new();
+    for_each_line(reader, |line| {
+        if let Some(instruction) = line2instruction(line)? {
+            sub.execute(instruction)?;
+            aimed.execute_with_aim(instruction)?;
         }
-    }
-    instructions
+        Ok(())
+    })?;
+    Ok((sub, aimed))
 }
 
-fn line2instruction(line: &str) -> Option<Instruction> {
-    let mut splitted = line.split_whitespace();
-    let instruction_type_str = splitted.next()?;
-    let instruction_size_str = splitted.next()?;
-    let instruction_size = instruction_size_str.parse::<usize>().unwrap();
+/// Parses a `<direction> <size>` line, blank lines give `None`.
+pub fn line2instruction(line: Line) -> Result<Option<Instruction>> {
+    if line.text.trim().is_empty() {
+        return Ok(None);
+    }
+    let (instruction_type_str, instruction_size) = keyword_value::<usize>(line)?;
     match instruction_type_str {
-        "forward" => return Some(Instruction::Forward(instruction_size)),
-        "down" => return Some(Instruction::Down(instruction_size)),
-        "up" => return Some(Instruction::Up(instruction_size)),
-        _ => panic!(),
+        "forward" => Ok(Some(Instruction::Forward(instruction_size))),
+        "down" => Ok(Some(Instruction::Down(instruction_size))),
+        "up" => Ok(Some(Instruction::Up(instruction_size))),
+        _ => {
+            let message = format!("unknown instruction `{}`", instruction_type_str);
+            Err(line.error_at(instruction_type_str, message))
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
     use super::*;
 
     fn instructions() -> Vec<Instruction> {
@@ -106,24 +150,110 @@ mod test {
     #[test]
     fn initial_is_zero() {
         let sub = Submarine::new();
-        assert_eq!(sub.horizontal_position(), 0);
+        assert_eq!(sub.horizontal_position().unwrap(), 0);
     }
 
     #[test]
     fn silver_example_test() {
         let mut sub = Submarine::new();
         for instruction in instructions() {
-            sub.execute(instruction);
+            sub.execute(instruction).unwrap();
         }
-        assert_eq!(sub.horizontal_position(), 150);
+        assert_eq!(sub.horizontal_position().unwrap(), 150);
     }
 
     #[test]
     fn gold_example_test() {
         let mut sub = Submarine::new();
         for instruction in instructions() {
-            sub.execute_with_aim(instruction);
+            sub.execute_with_aim(instruction).unwrap();
+        }
+        assert_eq!(sub.horizontal_position().unwrap(), 900);
+    }
+
+    #[test]
+    fn wide_courses() {
+        // Past isize: 2^40 forward, then 2^40 down the aim of 2^40.
+        let big = 1usize << 40;
+        let mut sub = Submarine::new();
+        for instruction in [Instruction::Down(big), Instruction::Forward(big)] {
+            sub.execute_with_aim(instruction).unwrap();
+        }
+        assert_eq!(sub.horizontal_position().unwrap(), 1 << 120);
+
+        // 2^62 down and forward fits, but not the position 2^62 * 2^124.
+        let big = 1usize << 62;
+        let mut sub = Submarine::new();
+        for instruction in [Instruction::Down(big), Instruction::Forward(big)] {
+            sub.execute_with_aim(instruction).unwrap();
+        }
+        assert!(sub.horizontal_position().is_err());
+        sub.execute_with_aim(Instruction::Down(usize::MAX)).unwrap();
+        let error = sub
+            .execute_with_aim(Instruction::Forward(usize::MAX))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer: the course of the submarine overflows i128"
+        );
+        let input = format!("down {0}\nforward {0}\n", usize::MAX);
+        assert!(dive_reader(input.as_bytes()).is_err());
+        assert!(Dive::gold(&Dive::parse(&input).unwrap()).is_err());
+    }
+
+    #[test]
+    fn reader_example_test() {
+        let input = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        let (sub, aimed) = dive_reader(input.as_bytes()).unwrap();
+        assert_eq!(sub.horizontal_position().unwrap(), 150);
+        assert_eq!(aimed.horizontal_position().unwrap(), 900);
+        let error = dive_reader("up 1\r\nback 2\r\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:2:1: unknown instruction `back`");
+    }
+
+    #[test]
+    fn parse_example() {
+        let input = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        assert_eq!(Dive::parse(input).unwrap(), instructions());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = Dive::parse("forward 5\n  sideways 2").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:3: unknown instruction `sideways`"
+        );
+        let error = Dive::parse("up -3").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:1:4: `-3` is not a valid usize");
+        let error = Dive::parse("down").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:1:5: missing value after `down`");
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        prop_oneof![
+            (0usize..1000).prop_map(Instruction::Down),
+            (0usize..1000).prop_map(Instruction::Up),
+            (0usize..1000).prop_map(Instruction::Forward),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn execute_is_order_independent(instructions in vec(instruction(), 0..100)) {
+            let mut sub = Submarine::new();
+            for &instruction in &instructions {
+                sub.execute(instruction).unwrap();
+            }
+
+            let (forward, depth): (Vec<Instruction>, Vec<Instruction>) = instructions
+                .iter()
+                .partition(|instruction| matches!(instruction, Instruction::Forward(_)));
+            let mut reordered = Submarine::new();
+            for instruction in depth.into_iter().chain(forward) {
+                reordered.execute(instruction).unwrap();
+            }
+            prop_assert_eq!(sub.horizontal_position().unwrap(), reordered.horizontal_position().unwrap());
         }
-        assert_eq!(sub.horizontal_position(), 900);
     }
 }
@@ -1,28 +1,55 @@
-use crate::util::file2str;
+use std::io::BufRead;
 
-pub fn solve() {
-    println!("--- Day 3: Binary Diagnostic ---");
-    let (diagnostics, significant_bits) = input();
-    let mut decoder = Decoder::new(significant_bits);
-    decoder.decode(diagnostics);
+use crate::error::{Error, Result};
+use crate::puzzle::Solution;
+use crate::util::{binary, for_each_line, lines, Line};
 
-    println!(
-        "\tsilver - power consumption {}",
-        decoder.power_consumption()
-    );
+pub struct BinaryDiagnostic;
 
-    println!(
-        "\tgold   - life support rating {}",
+impl Solution for BinaryDiagnostic {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Binary Diagnostic";
+    const INPUT: &'static str = "day03_binary_diagnostic.txt";
+    const SILVER_LABEL: &'static str = "power consumption";
+    const GOLD_LABEL: &'static str = "life support rating";
+
+    type Model = Report;
+    type Silver = u128;
+    type Gold = u128;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        let (diagnostics, significant_bits) = parse_report(input)?;
+        Ok(Report {
+            diagnostics,
+            significant_bits,
+        })
+    }
+
+    fn silver(report: &Self::Model) -> Result<Self::Silver> {
+        let mut decoder = Decoder::new(report.significant_bits);
+        decoder.decode(report.diagnostics.clone());
+        Ok(decoder.power_consumption())
+    }
+
+    fn gold(report: &Self::Model) -> Result<Self::Gold> {
+        let mut decoder = Decoder::new(report.significant_bits);
+        decoder.decode(report.diagnostics.clone());
         decoder.life_support_rating()
-    );
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub diagnostics: Vec<usize>,
+    pub significant_bits: usize,
 }
 
-struct Decoder {
+pub struct Decoder {
     significant_bits: usize,
     gamma: usize,
     epsilon: usize,
-    oxygen: usize,
-    co2: usize,
+    oxygen: Option<usize>,
+    co2: Option<usize>,
 }
 
 impl Decoder {
@@ -31,8 +58,8 @@ impl Decoder {
             significant_bits,
             gamma: 0,
             epsilon: 0,
-            oxygen: 0,
-            co2: 0,
+            oxygen: None,
+            co2: None,
         }
     }
 
@@ -47,67 +74,128 @@ impl Decoder {
         self.co2 = c02_scrubber_rating(&diagnostics, self.significant_bits);
     }
 
-    pub fn power_consumption(&self) -> usize {
-        self.gamma * self.epsilon
+    pub fn power_consumption(&self) -> u128 {
+        product(self.gamma, self.epsilon)
     }
 
-    pub fn life_support_rating(&self) -> usize {
-        self.oxygen * self.co2
+    /// Fails when a rating filter does not end at exactly one diagnostic.
+    pub fn life_support_rating(&self) -> Result<u128> {
+        let rating = |rating: Option<usize>, name: &str| {
+            rating.ok_or_else(|| {
+                let message = format!("the {} rating does not narrow down to one diagnostic", name);
+                Error::NoAnswer(message)
+            })
+        };
+        let oxygen = rating(self.oxygen, "oxygen generator")?;
+        Ok(product(oxygen, rating(self.co2, "CO2 scrubber")?))
     }
 }
 
-fn oxygen_generator_rating(data: &[usize], significant_bits: usize) -> usize {
-    let mut data: Vec<usize> = Vec::from(data);
-    for bit in (0..significant_bits).rev() {
-        let bitcount = calculate_bitcount(&data, bit);
-        let keep_ones = bitcount * 2 >= data.len();
-        data = data
-            .iter()
-            .filter(|&&x| {
-                if keep_ones {
-                    bit_read(x, bit)
-                } else {
-                    !bit_read(x, bit)
-                }
-            })
-            .map(|&x| x)
-            .collect();
+/// The set bits of every position, counted one diagnostic at a time. Enough
+/// for the power consumption; the life support rating needs the whole report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitCounts {
+    pub significant_bits: usize,
+    pub total: usize,
+    pub ones: Vec<usize>,
+}
 
-        if data.len() == 1 {
-            return *data.first().unwrap();
+impl BitCounts {
+    pub fn new(significant_bits: usize) -> Self {
+        Self {
+            significant_bits,
+            total: 0,
+            ones: vec![0; significant_bits],
+        }
+    }
+
+    pub fn push(&mut self, diagnostic: usize) {
+        for (bit, count) in self.ones.iter_mut().enumerate() {
+            if bit_read(diagnostic, bit) {
+                *count += 1;
+            }
         }
+        self.total += 1;
     }
-    0
+
+    pub fn power_consumption(&self) -> u128 {
+        let (gamma, epsilon) = calculate_rates(&self.ones, self.total, self.significant_bits);
+        product(gamma, epsilon)
+    }
+}
+
+/// Counts the bits of a report straight from a reader without keeping the
+/// diagnostics in memory. The first diagnostic sets the width.
+pub fn bit_counts_reader<R: BufRead>(reader: R) -> Result<BitCounts> {
+    let mut counts: Option<BitCounts> = None;
+    for_each_line(reader, |line| {
+        let text = line.text.trim();
+        if text.is_empty() {
+            return Ok(());
+        }
+        let counts = match &mut counts {
+            Some(counts) => counts,
+            None => counts.insert(BitCounts::new(check_width(text.len())?)),
+        };
+        counts.push(diagnostic(line, counts.significant_bits)?);
+        Ok(())
+    })?;
+    counts.ok_or_else(empty_report)
 }
 
-fn c02_scrubber_rating(data: &[usize], significant_bits: usize) -> usize {
+fn oxygen_generator_rating(data: &[usize], significant_bits: usize) -> Option<usize> {
     let mut data: Vec<usize> = Vec::from(data);
     for bit in (0..significant_bits).rev() {
+        if data.len() == 1 {
+            break;
+        }
         let bitcount = calculate_bitcount(&data, bit);
-        let keep_zeroes = bitcount * 2 >= data.len();
-        data = data
-            .iter()
-            .filter(|&&x| {
-                if keep_zeroes {
-                    !bit_read(x, bit)
-                } else {
-                    bit_read(x, bit)
-                }
-            })
-            .map(|x| *x)
-            .collect();
+        let keep_ones = bitcount * 2 >= data.len();
+        data.retain(|&x| {
+            if keep_ones {
+                bit_read(x, bit)
+            } else {
+                !bit_read(x, bit)
+            }
+        });
+    }
+    match data[..] {
+        [rating] => Some(rating),
+        _ => None,
+    }
+}
 
+fn c02_scrubber_rating(data: &[usize], significant_bits: usize) -> Option<usize> {
+    let mut data: Vec<usize> = Vec::from(data);
+    for bit in (0..significant_bits).rev() {
         if data.len() == 1 {
-            return *data.first().unwrap();
+            break;
         }
+        let bitcount = calculate_bitcount(&data, bit);
+        let keep_zeroes = bitcount * 2 >= data.len();
+        data.retain(|&x| {
+            if keep_zeroes {
+                !bit_read(x, bit)
+            } else {
+                bit_read(x, bit)
+            }
+        });
+    }
+    match data[..] {
+        [rating] => Some(rating),
+        _ => None,
     }
-    0
+}
+
+/// The answers multiply two diagnostics, which always fits a `u128`.
+fn product(a: usize, b: usize) -> u128 {
+    a as u128 * b as u128
 }
 
 fn calculate_bitcounts(data: &[usize], significant_bits: usize) -> Vec<usize> {
     let mut bitcounts = vec![0usize; significant_bits];
     for (index, value) in bitcounts.iter_mut().enumerate() {
-        *value = calculate_bitcount(&data, index);
+        *value = calculate_bitcount(data, index);
     }
     bitcounts
 }
@@ -123,7 +211,7 @@ fn calculate_rates(
 ) -> (usize, usize) {
     let mut gamma = 0;
     for (index, bitcount) in bitcounts.iter().enumerate() {
-        if *bitcount as usize > total_count / 2 {
+        if *bitcount > total_count / 2 {
             bit_set(&mut gamma, index);
         }
     }
@@ -167,33 +255,57 @@ fn bit_print(input: usize, significant: usize) {
     println!("{}", string);
 }
 
-fn input() -> (Vec<usize>, usize) {
-    let input_string = file2str("inputs/day03_binary_diagnostic.txt");
-
-    let significant_bits = &input_string
+fn parse_report(input_string: &str) -> Result<(Vec<usize>, usize)> {
+    let significant_bits = input_string
         .split_whitespace()
         .next()
         .map(|line| line.len())
-        .expect("Could not read length of first line in input");
+        .ok_or_else(empty_report)?;
+    check_width(significant_bits)?;
 
     let mut data: Vec<usize> = Vec::new();
-    for line in input_string.lines() {
-        if let Some(bits) = bitline2usize(line) {
-            data.push(bits);
+    for line in lines(input_string) {
+        if line.text.trim().is_empty() {
+            continue;
         }
+        data.push(diagnostic(line, significant_bits)?);
+    }
+    Ok((data, significant_bits))
+}
+
+fn empty_report() -> Error {
+    Error::InvalidModel(String::from("the diagnostic report is empty"))
+}
+
+fn check_width(significant_bits: usize) -> Result<usize> {
+    if significant_bits >= usize::BITS as usize {
+        let message = format!("{} bit diagnostics are too wide", significant_bits);
+        return Err(Error::InvalidModel(message));
     }
-    (data, *significant_bits)
+    Ok(significant_bits)
 }
 
-fn bitline2usize(line: &str) -> Option<usize> {
-    if let Ok(value) = usize::from_str_radix(line, 2) {
-        return Some(value);
+/// A diagnostic line that must be `significant_bits` wide.
+fn diagnostic(line: Line, significant_bits: usize) -> Result<usize> {
+    let bits = bitline2usize(line)?;
+    let length = line.text.trim().len();
+    if length != significant_bits {
+        let message = format!("expected {} bits, found {}", significant_bits, length);
+        return Err(line.error(1, message));
     }
-    return None;
+    Ok(bits)
+}
+
+/// A diagnostic line such as `10110`.
+pub fn bitline2usize(line: Line) -> Result<usize> {
+    binary::<usize>(line)
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -277,6 +389,20 @@ mod test {
         assert_eq!(decoder.power_consumption(), 198);
     }
 
+    #[test]
+    fn wide_diagnostics() {
+        // 40 bits, so both answers are past u64.
+        let (odd, even) = (0xAA_AAAA_AAAA, 0x55_5555_5555);
+        let diagnostics = vec![odd, odd | 1, even];
+        let mut decoder = Decoder::new(40);
+        decoder.decode(diagnostics);
+        assert_eq!(decoder.power_consumption(), 268650182135729085335324);
+        assert_eq!(
+            decoder.life_support_rating().unwrap(),
+            268650182136462093087175
+        );
+    }
+
     #[test]
     fn oxygen_test() {
         let diagnostics = vec![
@@ -284,7 +410,7 @@ mod test {
             0b11001, 0b00010, 0b01010,
         ];
         let rating = oxygen_generator_rating(&diagnostics, 5);
-        assert_eq!(rating, 23);
+        assert_eq!(rating, Some(23));
     }
 
     #[test]
@@ -294,6 +420,76 @@ mod test {
             0b11001, 0b00010, 0b01010,
         ];
         let rating = c02_scrubber_rating(&diagnostics, 5);
-        assert_eq!(rating, 10);
+        assert_eq!(rating, Some(10));
+    }
+
+    #[test]
+    fn ratings_that_filter_out_everything() {
+        // Every diagnostic left has a 0 in the middle, so the CO2 filter
+        // keeps the 1s, of which there are none.
+        let diagnostics = vec![0b0110, 0b0001, 0b0100];
+        assert_eq!(oxygen_generator_rating(&diagnostics, 4), Some(0b0110));
+        assert_eq!(c02_scrubber_rating(&diagnostics, 4), None);
+        let mut decoder = Decoder::new(4);
+        decoder.decode(diagnostics);
+        let error = decoder.life_support_rating().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer: the CO2 scrubber rating does not narrow down to one diagnostic"
+        );
+
+        let repeated = vec![0b10, 0b01, 0b10, 0b01];
+        assert_eq!(oxygen_generator_rating(&repeated, 2), None);
+        assert_eq!(oxygen_generator_rating(&[0b11], 2), Some(0b11));
+        assert_eq!(c02_scrubber_rating(&[0b11], 2), Some(0b11));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = BinaryDiagnostic::parse("0101\n0121\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:3: `0121` is not a binary number"
+        );
+        let error = BinaryDiagnostic::parse("0101\n011\n").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:2:1: expected 4 bits, found 3");
+        assert!(BinaryDiagnostic::parse("\n").is_err());
+    }
+
+    #[test]
+    fn reader_example_test() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+        let counts = bit_counts_reader(input.as_bytes()).unwrap();
+        assert_eq!(counts.ones, [5, 7, 8, 5, 7]);
+        assert_eq!(counts.total, 12);
+        assert_eq!(counts.power_consumption(), 198);
+    }
+
+    #[test]
+    fn reader_errors() {
+        let error = bit_counts_reader("\n0101\n011\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:3:1: expected 4 bits, found 3");
+        let error = bit_counts_reader("0101\n0121\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:3: `0121` is not a binary number"
+        );
+        assert!(bit_counts_reader("\n \n".as_bytes()).is_err());
+        assert!(bit_counts_reader("1".repeat(70).as_bytes()).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn rates_cover_every_bit(
+            (significant_bits, diagnostics) in (1usize..16)
+                .prop_flat_map(|bits| (Just(bits), vec(0usize..1 << bits, 1..100)))
+        ) {
+            let bitcounts = calculate_bitcounts(&diagnostics, significant_bits);
+            let (gamma, epsilon) =
+                calculate_rates(&bitcounts, diagnostics.len(), significant_bits);
+            prop_assert_eq!(gamma | epsilon, (1 << significant_bits) - 1);
+            prop_assert_eq!(gamma & epsilon, 0);
+        }
     }
 }
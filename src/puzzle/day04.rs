@@ -1,27 +1,55 @@
 use itertools::Itertools;
 
-const SQUARE_SIZE: usize = 5;
-
-pub fn solve() {
-    println!("--- Day 4: Giant Squid ---");
-    let (draw_numbers, board_data) = input();
-    let mut boards = board_data
-        .into_iter()
-        .enumerate()
-        .map(|(index, data)| Board::with_id(index, &data))
-        .collect::<Vec<Board>>();
-    let board_wins = bingo(&draw_numbers, &mut boards);
-
-    println!("\tsilver - score {}", board_wins.first().unwrap().1);
-
-    let (last_id, last_score) = {
-        let last = board_wins.last().unwrap();
-        (last.0, last.1)
-    };
-    println!("\tgold   - id {}, score {}", last_id, last_score);
+use crate::error::{Error, Result};
+use crate::puzzle::Solution;
+use crate::util::{comma_list, grid, header_and_sections, Line};
+
+pub struct GiantSquid;
+
+impl Solution for GiantSquid {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Giant Squid";
+    const INPUT: &'static str = "day04_giant_squid.txt";
+    const SILVER_LABEL: &'static str = "score";
+    const GOLD_LABEL: &'static str = "last winner score";
+
+    type Model = Game;
+    type Silver = usize;
+    type Gold = usize;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        let (draw_numbers, board_data) = parse_game(input)?;
+        let boards = board_data
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| Board::with_id(index, &data))
+            .collect::<Result<Vec<Board>>>()?;
+        Ok(Game {
+            draw_numbers,
+            boards,
+        })
+    }
+
+    fn silver(game: &Self::Model) -> Result<Self::Silver> {
+        let board_wins = bingo(&game.draw_numbers, &mut game.boards.clone());
+        let (_, score) = board_wins.first().ok_or_else(no_winner)?;
+        Ok(*score)
+    }
+
+    fn gold(game: &Self::Model) -> Result<Self::Gold> {
+        let board_wins = bingo(&game.draw_numbers, &mut game.boards.clone());
+        let (_, score) = board_wins.last().ok_or_else(no_winner)?;
+        Ok(*score)
+    }
 }
 
-fn bingo(draw_numbers: &Vec<u8>, boards: &mut Vec<Board>) -> Vec<(usize, usize)> {
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub draw_numbers: Vec<u8>,
+    pub boards: Vec<Board>,
+}
+
+pub fn bingo(draw_numbers: &Vec<u8>, boards: &mut Vec<Board>) -> Vec<(usize, usize)> {
     let mut board_wins = Vec::new();
     for number in draw_numbers {
         for board in &mut *boards {
@@ -36,40 +64,50 @@ fn bingo(draw_numbers: &Vec<u8>, boards: &mut Vec<Board>) -> Vec<(usize, usize)>
     board_wins
 }
 
-fn input() -> (Vec<u8>, Vec<Vec<u8>>) {
-    let input_string = crate::util::file2str("inputs/day04_giant_squid.txt");
-    let mut splitted = input_string.split_whitespace();
-
-    let draw_numbers = parse_draw_numbers(
-        splitted
-            .next()
-            .expect("Could not parse draw numbers as first line in input"),
-    );
+fn no_winner() -> Error {
+    Error::NoAnswer(String::from("no board wins with the drawn numbers"))
+}
 
-    let values: Vec<u8> = splitted.map(|x| x.parse::<u8>().unwrap()).collect();
+/// The draw numbers are the header of the input, every following section is
+/// a square board with one row per line.
+fn parse_game(input_string: &str) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+    let (header, sections) = header_and_sections(input_string)?;
+    if let Some(line) = header.get(1) {
+        return Err(line.error(1, "expected a blank line after the draw numbers"));
+    }
+    let draw_numbers = parse_draw_numbers(header[0])?;
 
     let mut board_data: Vec<Vec<u8>> = Vec::new();
-    for chunk in values.chunks(SQUARE_SIZE * SQUARE_SIZE) {
-        board_data.push(Vec::from(chunk));
+    for section in sections {
+        let rows = grid::<u8>(&section)?;
+        if rows[0].len() != rows.len() {
+            let message = format!(
+                "expected {} numbers in a board of {} rows, found {}",
+                rows.len(),
+                rows.len(),
+                rows[0].len()
+            );
+            return Err(section[0].error(1, message));
+        }
+        board_data.push(rows.concat());
     }
 
-    (draw_numbers, board_data)
+    Ok((draw_numbers, board_data))
 }
 
-fn parse_draw_numbers(data: &str) -> Vec<u8> {
-    data.split_terminator(',')
-        .map(|val| val.parse::<u8>().unwrap())
-        .collect()
+/// The comma separated draw numbers on the first line of a game.
+pub fn parse_draw_numbers(line: Line) -> Result<Vec<u8>> {
+    comma_list::<u8>(line)
 }
 
 #[derive(Debug, Clone, PartialEq)]
-enum DrawResult {
+pub enum DrawResult {
     None,
     Win(usize),
 }
 
 #[derive(Debug, Clone)]
-struct Board {
+pub struct Board {
     pub id: usize,
     pub has_won: bool,
     square_size: usize,
@@ -78,25 +116,28 @@ struct Board {
 }
 
 impl Board {
-    pub fn new(data: &[u8]) -> Self {
-        assert!(data.len() >= 4);
-        assert!(is_square(data.len()));
+    pub fn new(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 || !is_square(data.len()) {
+            let message = format!(
+                "a board of {} numbers is not a square of 2x2 or more",
+                data.len()
+            );
+            return Err(Error::InvalidModel(message));
+        }
 
-        let board = Self {
+        Ok(Self {
             square_size: (data.len() as f64).sqrt() as usize,
             values: data.to_owned(),
             drawn: vec![false; data.len()],
             id: 0,
             has_won: false,
-        };
-
-        board
+        })
     }
 
-    pub fn with_id(id: usize, data: &[u8]) -> Self {
-        let mut board = Board::new(data);
+    pub fn with_id(id: usize, data: &[u8]) -> Result<Self> {
+        let mut board = Board::new(data)?;
         board.id = id;
-        board
+        Ok(board)
     }
 
     pub fn draw(&mut self, value: u8) -> DrawResult {
@@ -118,7 +159,7 @@ impl Board {
 
     fn check_rows(&self) -> bool {
         for chunk in &self.drawn.iter().chunks(self.square_size) {
-            if chunk.filter(|&&x| x == true).count() == self.square_size {
+            if chunk.filter(|&&x| x).count() == self.square_size {
                 return true;
             }
         }
@@ -132,7 +173,7 @@ impl Board {
                 .iter()
                 .skip(column_index)
                 .step_by(self.square_size)
-                .filter(|&&x| x == true)
+                .filter(|&&x| x)
                 .count();
             if count == self.square_size {
                 return true;
@@ -167,13 +208,15 @@ fn is_square(value: usize) -> bool {
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn test_row_win() {
         let data = vec![1, 2, 3, 4];
 
-        let mut board = Board::new(&data);
+        let mut board = Board::new(&data).unwrap();
         assert_eq!(board.draw(1), DrawResult::None);
         assert_eq!(board.draw(2), DrawResult::Win(14));
     }
@@ -182,8 +225,65 @@ mod test {
     fn test_column_win() {
         let data = vec![1, 2, 3, 4];
 
-        let mut board = Board::new(&data);
+        let mut board = Board::new(&data).unwrap();
         assert_eq!(board.draw(2), DrawResult::None);
         assert_eq!(board.draw(4), DrawResult::Win(16));
     }
+
+    #[test]
+    fn rejects_non_square_board() {
+        assert!(Board::new(&[1, 2, 3]).is_err());
+        assert!(Board::new(&[1, 2, 3, 4, 5, 6]).is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = GiantSquid::parse("1,2,x\n\n1 2").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:1:5: `x` is not a valid u8");
+        let error = GiantSquid::parse("1,2\n\n1 256").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:3:3: `256` is not a valid u8");
+        let error = GiantSquid::parse("1,2\n\n1 2\n3 4 5\n").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:4:1: expected 2 values, found 3");
+        let error = GiantSquid::parse("1,2\n\n1 2 3\n4 5 6\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:3:1: expected 2 numbers in a board of 2 rows, found 3"
+        );
+        let error = GiantSquid::parse("1,2\n3,4\n\n1 2\n3 4\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:2:1: expected a blank line after the draw numbers"
+        );
+    }
+
+    #[test]
+    fn no_winner_is_an_error() {
+        let board = "1 2 3 4 5\n".repeat(5);
+        let game = GiantSquid::parse(&format!("1,2\n\n{}", board)).unwrap();
+        assert!(matches!(GiantSquid::silver(&game), Err(Error::NoAnswer(_))));
+    }
+
+    /// A square board of distinct numbers together with the order they are
+    /// drawn in.
+    fn board_and_draws() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+        (2usize..=5).prop_flat_map(|size| {
+            let numbers = Just((0..=u8::MAX).collect::<Vec<u8>>()).prop_shuffle();
+            numbers.prop_flat_map(move |numbers| {
+                let values = numbers[..size * size].to_vec();
+                (Just(values.clone()), Just(values).prop_shuffle())
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn wins_once_all_numbers_are_drawn((values, draws) in board_and_draws()) {
+            let mut board = Board::new(&values).unwrap();
+            let won = draws
+                .iter()
+                .any(|&number| matches!(board.draw(number), DrawResult::Win(_)));
+            prop_assert!(won);
+            prop_assert!(board.has_won);
+        }
+    }
 }
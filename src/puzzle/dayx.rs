@@ -1,17 +1,48 @@
-pub fn solve() {
-    println!("--- Day x: name ---");
+//! --- Day x: Name ---
+use crate::error::Result;
+use crate::puzzle::Solution;
 
-    println!("\tsilver - ");
+pub struct Name;
 
-    println!("\tgold   - ");
+impl Solution for Name {
+    const DAY: u8 = 0;
+    const TITLE: &'static str = "Name";
+    const INPUT: &'static str = "dayxx_name.txt";
+    const SILVER_LABEL: &'static str = "answer";
+    const GOLD_LABEL: &'static str = "answer";
+
+    type Model = Vec<usize>;
+    type Silver = usize;
+    type Gold = usize;
+
+    fn parse(_input: &str) -> Result<Self::Model> {
+        Ok(Vec::new())
+    }
+
+    fn silver(_model: &Self::Model) -> Result<Self::Silver> {
+        Ok(0)
+    }
+
+    fn gold(_model: &Self::Model) -> Result<Self::Gold> {
+        Ok(0)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn silver_example_test() {
+        let model = Name::parse(EXAMPLE).unwrap();
+        assert_eq!(Name::silver(&model).unwrap(), 0);
+    }
+
     #[test]
-    fn foo() {
-        assert_eq!(0, 0);
+    fn gold_example_test() {
+        let model = Name::parse(EXAMPLE).unwrap();
+        assert_eq!(Name::gold(&model).unwrap(), 0);
     }
 }
@@ -1,4 +1,163 @@
+use std::env;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    Silver,
+    Gold,
+}
+
+/// A puzzle day. The input text is parsed once into `Model`, which both parts
+/// then solve independently. `INPUT` is the file name of the input inside the
+/// input directory, see [`input_dir`].
+pub trait Solution {
+    const DAY: u8;
+    const TITLE: &'static str;
+    const INPUT: &'static str;
+    const SILVER_LABEL: &'static str;
+    const GOLD_LABEL: &'static str;
+
+    type Model;
+    type Silver: Display;
+    type Gold: Display;
+
+    fn parse(input: &str) -> Result<Self::Model>;
+    fn silver(model: &Self::Model) -> Result<Self::Silver>;
+    fn gold(model: &Self::Model) -> Result<Self::Gold>;
+}
+
+/// The answer to one part of a day, together with what it measures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub day: u8,
+    pub part: Part,
+    pub label: &'static str,
+    pub value: String,
+}
+
+/// How long parsing and each solved part took.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub silver: Option<Duration>,
+    pub gold: Option<Duration>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.silver.unwrap_or_default() + self.gold.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub answers: Vec<Answer>,
+    pub timings: Timings,
+}
+
+/// Type erased entry in the registry, so days can be handled uniformly.
+pub struct Day {
+    pub number: u8,
+    pub title: &'static str,
+    pub input: &'static str,
+    pub solve: fn(&str, &[Part]) -> Result<Solved>,
+}
+
+pub const DAYS: &[Day] = &[
+    day::<day01::SonarSweep>(),
+    day::<day02::Dive>(),
+    day::<day03::BinaryDiagnostic>(),
+    day::<day04::GiantSquid>(),
+];
+
+impl Day {
+    pub fn input_path(&self) -> PathBuf {
+        input_dir().join(self.input)
+    }
+}
+
+/// Directory holding the puzzle inputs, `AOC_INPUT_DIR` if set and otherwise
+/// the committed `inputs` directory of this crate.
+pub fn input_dir() -> PathBuf {
+    match env::var_os("AOC_INPUT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("inputs"),
+    }
+}
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+const fn day<S: Solution>() -> Day {
+    Day {
+        number: S::DAY,
+        title: S::TITLE,
+        input: S::INPUT,
+        solve: solve::<S>,
+    }
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Solved> {
+    let start = Instant::now();
+    let model = S::parse(input)?;
+    let mut timings = Timings {
+        parse: start.elapsed(),
+        ..Timings::default()
+    };
+
+    let mut answers = Vec::new();
+    for &part in parts {
+        let start = Instant::now();
+        let (label, value) = match part {
+            Part::Silver => {
+                let value = S::silver(&model)?;
+                timings.silver = Some(start.elapsed());
+                (S::SILVER_LABEL, value.to_string())
+            }
+            Part::Gold => {
+                let value = S::gold(&model)?;
+                timings.gold = Some(start.elapsed());
+                (S::GOLD_LABEL, value.to_string())
+            }
+        };
+        answers.push(Answer {
+            day: S::DAY,
+            part,
+            label,
+            value,
+        });
+    }
+    Ok(Solved { answers, timings })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::file2str;
+
+    #[test]
+    fn answers_follow_requested_parts() {
+        let day = find(1).unwrap();
+        let input = file2str(day.input_path()).unwrap();
+        let solved = (day.solve)(&input, &[Part::Gold]).unwrap();
+        let expected = Answer {
+            day: 1,
+            part: Part::Gold,
+            label: "increases",
+            value: String::from("1702"),
+        };
+        assert_eq!(solved.answers, [expected]);
+        assert!(solved.timings.silver.is_none());
+        assert!(solved.timings.gold.is_some());
+    }
+}
@@ -0,0 +1,405 @@
+use std::time::Duration;
+
+use crate::answers::Verdict;
+use crate::puzzle::{Answer, Day, Part, Timings};
+
+pub fn print_day(day: &Day, answers: &[Answer]) {
+    println!("--- Day {}: {} ---", day.number, day.title);
+    for answer in answers {
+        println!("\t{}", format_answer(answer));
+    }
+}
+
+fn format_answer(answer: &Answer) -> String {
+    let part = match answer.part {
+        Part::Silver => "silver",
+        Part::Gold => "gold  ",
+    };
+    format!("{} - {} {}", part, answer.label, answer.value)
+}
+
+fn part_name(part: Part) -> &'static str {
+    match part {
+        Part::Silver => "silver",
+        Part::Gold => "gold",
+    }
+}
+
+/// One aligned row per answer.
+pub fn answers_table(rows: &[(&Day, Vec<Answer>)]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<20} {:<6}  {:<20} {}\n",
+        "day", "title", "part", "label", "answer"
+    );
+    for (day, answers) in rows {
+        for answer in answers {
+            table += &format!(
+                "{:>3}  {:<20} {:<6}  {:<20} {}\n",
+                day.number,
+                day.title,
+                part_name(answer.part),
+                answer.label,
+                answer.value
+            );
+        }
+    }
+    table
+}
+
+pub fn answers_json(rows: &[(&Day, Vec<Answer>)]) -> String {
+    format!(
+        "{{\n  \"answers\": [\n{}\n  ]\n}}\n",
+        answer_objects(rows).join(",\n")
+    )
+}
+
+fn answer_objects(rows: &[(&Day, Vec<Answer>)]) -> Vec<String> {
+    rows.iter()
+        .flat_map(|(day, answers)| {
+            answers.iter().map(move |answer| {
+                format!(
+                    "    {{\"day\": {}, \"title\": {}, \"part\": \"{}\", \"label\": {}, \"answer\": {}}}",
+                    day.number,
+                    json_string(day.title),
+                    part_name(answer.part),
+                    json_string(answer.label),
+                    json_string(&answer.value),
+                )
+            })
+        })
+        .collect()
+}
+
+/// One record per answer with a header row, quoted where needed.
+pub fn answers_csv(rows: &[(&Day, Vec<Answer>)]) -> String {
+    let mut csv = String::from("day,title,part,label,answer\n");
+    for (day, answers) in rows {
+        for answer in answers {
+            let fields = [
+                day.number.to_string(),
+                csv_field(day.title),
+                part_name(answer.part).to_string(),
+                csv_field(answer.label),
+                csv_field(&answer.value),
+            ];
+            csv += &fields.join(",");
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per day with both parts, the shape of a README progress table.
+pub fn answers_markdown(rows: &[(&Day, Vec<Answer>)]) -> String {
+    let mut table = String::from("| Day | Title | Silver | Gold |\n| ---: | --- | ---: | ---: |\n");
+    for (day, answers) in rows {
+        let value = |part: Part| {
+            answers
+                .iter()
+                .find(|answer| answer.part == part)
+                .map_or(String::from("-"), |answer| markdown_cell(&answer.value))
+        };
+        table += &format!(
+            "| {} | {} | {} | {} |\n",
+            day.number,
+            markdown_cell(day.title),
+            value(Part::Silver),
+            value(Part::Gold)
+        );
+    }
+    table
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+pub fn print_verdict(answer: &Answer, verdict: &Verdict) {
+    println!("{}", format_verdict(answer, verdict));
+}
+
+fn format_verdict(answer: &Answer, verdict: &Verdict) -> String {
+    let outcome = match verdict {
+        Verdict::Pass => format!("pass     {}", answer.value),
+        Verdict::Fail { expected } => {
+            format!("FAIL     expected {}, got {}", expected, answer.value)
+        }
+        Verdict::Missing => format!("missing  got {}", answer.value),
+    };
+    format!(
+        "day {:>2} {:<6}  {}",
+        answer.day,
+        part_name(answer.part),
+        outcome
+    )
+}
+
+pub fn print_timings(rows: &[(&Day, Timings)]) {
+    print!("{}", timings_table(rows));
+}
+
+fn timings_table(rows: &[(&Day, Timings)]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<20} {:>10} {:>10} {:>10} {:>10}\n",
+        "day", "title", "parse", "silver", "gold", "total"
+    );
+    let mut total = Timings::default();
+    for (day, timings) in rows {
+        table += &format!(
+            "{:>3}  {:<20} {:>10} {:>10} {:>10} {:>10}\n",
+            day.number,
+            day.title,
+            format_duration(Some(timings.parse)),
+            format_duration(timings.silver),
+            format_duration(timings.gold),
+            format_duration(Some(timings.total())),
+        );
+        total.parse += timings.parse;
+        total.silver = add_duration(total.silver, timings.silver);
+        total.gold = add_duration(total.gold, timings.gold);
+    }
+    table += &format!(
+        "{:>3}  {:<20} {:>10} {:>10} {:>10} {:>10}\n",
+        "",
+        "total",
+        format_duration(Some(total.parse)),
+        format_duration(total.silver),
+        format_duration(total.gold),
+        format_duration(Some(total.total())),
+    );
+    table
+}
+
+fn add_duration(sum: Option<Duration>, value: Option<Duration>) -> Option<Duration> {
+    match (sum, value) {
+        (Some(sum), Some(value)) => Some(sum + value),
+        (sum, value) => sum.or(value),
+    }
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{:.1?}", duration),
+        None => String::from("-"),
+    }
+}
+
+pub fn timings_json(rows: &[(&Day, Timings)]) -> String {
+    let total: Duration = rows.iter().map(|(_, timings)| timings.total()).sum();
+    format!(
+        "{{\n  \"days\": [\n{}\n  ],\n  \"total_ns\": {}\n}}\n",
+        timing_objects(rows).join(",\n"),
+        total.as_nanos()
+    )
+}
+
+/// The answers and the timings in one document, for `--format json` together
+/// with `--time=json`.
+pub fn run_json(answers: &[(&Day, Vec<Answer>)], timings: &[(&Day, Timings)]) -> String {
+    let total: Duration = timings.iter().map(|(_, timings)| timings.total()).sum();
+    format!(
+        "{{\n  \"answers\": [\n{}\n  ],\n  \"days\": [\n{}\n  ],\n  \"total_ns\": {}\n}}\n",
+        answer_objects(answers).join(",\n"),
+        timing_objects(timings).join(",\n"),
+        total.as_nanos()
+    )
+}
+
+fn timing_objects(rows: &[(&Day, Timings)]) -> Vec<String> {
+    rows.iter()
+        .map(|(day, timings)| {
+            format!(
+                "    {{\"day\": {}, \"title\": {}, \"parse_ns\": {}, \"silver_ns\": {}, \"gold_ns\": {}, \"total_ns\": {}}}",
+                day.number,
+                json_string(day.title),
+                timings.parse.as_nanos(),
+                json_nanos(timings.silver),
+                json_nanos(timings.gold),
+                timings.total().as_nanos(),
+            )
+        })
+        .collect()
+}
+
+fn json_nanos(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => duration.as_nanos().to_string(),
+        None => String::from("null"),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut string = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            '\n' => string.push_str("\\n"),
+            '\t' => string.push_str("\\t"),
+            c if (c as u32) < 0x20 => string.push_str(&format!("\\u{:04x}", c as u32)),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::puzzle;
+
+    fn timings() -> Timings {
+        Timings {
+            parse: Duration::from_micros(10),
+            silver: Some(Duration::from_micros(5)),
+            gold: None,
+        }
+    }
+
+    #[test]
+    fn format_answer_test() {
+        let answer = Answer {
+            day: 3,
+            part: Part::Gold,
+            label: "life support rating",
+            value: String::from("230"),
+        };
+        assert_eq!(format_answer(&answer), "gold   - life support rating 230");
+    }
+
+    fn answers() -> Vec<(&'static Day, Vec<Answer>)> {
+        let answer = |day: u8, part: Part, label: &'static str, value: &str| Answer {
+            day,
+            part,
+            label,
+            value: String::from(value),
+        };
+        vec![
+            (
+                puzzle::find(2).unwrap(),
+                vec![
+                    answer(2, Part::Silver, "horizontal position", "150"),
+                    answer(2, Part::Gold, "horizontal position", "900"),
+                ],
+            ),
+            (
+                puzzle::find(4).unwrap(),
+                vec![answer(4, Part::Gold, "last winner score", "1,924")],
+            ),
+        ]
+    }
+
+    #[test]
+    fn answers_table_test() {
+        let table = answers_table(&answers());
+        let expected = "\
+day  title                part    label                answer
+  2  Dive!                silver  horizontal position  150
+  2  Dive!                gold    horizontal position  900
+  4  Giant Squid          gold    last winner score    1,924
+";
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn answers_json_test() {
+        let json = answers_json(&answers()[1..]);
+        let expected = "{\n  \"answers\": [\n    {\"day\": 4, \"title\": \"Giant Squid\", \"part\": \"gold\", \"label\": \"last winner score\", \"answer\": \"1,924\"}\n  ]\n}\n";
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn answers_csv_test() {
+        let expected = "\
+day,title,part,label,answer
+2,Dive!,silver,horizontal position,150
+2,Dive!,gold,horizontal position,900
+4,Giant Squid,gold,last winner score,\"1,924\"
+";
+        assert_eq!(answers_csv(&answers()), expected);
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn answers_markdown_test() {
+        let expected = "\
+| Day | Title | Silver | Gold |
+| ---: | --- | ---: | ---: |
+| 2 | Dive! | 150 | 900 |
+| 4 | Giant Squid | - | 1,924 |
+";
+        assert_eq!(answers_markdown(&answers()), expected);
+        assert_eq!(markdown_cell("a|b"), "a\\|b");
+    }
+
+    #[test]
+    fn format_verdict_test() {
+        let answer = Answer {
+            day: 1,
+            part: Part::Gold,
+            label: "increases",
+            value: String::from("5"),
+        };
+        assert_eq!(
+            format_verdict(&answer, &Verdict::Pass),
+            "day  1 gold    pass     5"
+        );
+        let verdict = Verdict::Fail {
+            expected: String::from("6"),
+        };
+        assert_eq!(
+            format_verdict(&answer, &verdict),
+            "day  1 gold    FAIL     expected 6, got 5"
+        );
+        assert_eq!(
+            format_verdict(&answer, &Verdict::Missing),
+            "day  1 gold    missing  got 5"
+        );
+    }
+
+    #[test]
+    fn timings_table_test() {
+        let day = puzzle::find(1).unwrap();
+        let table = timings_table(&[(day, timings()), (day, timings())]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "  1  Sonar Sweep              10.0µs      5.0µs          -     15.0µs"
+        );
+        assert_eq!(
+            lines[3],
+            "     total                    20.0µs     10.0µs          -     30.0µs"
+        );
+    }
+
+    #[test]
+    fn timings_json_test() {
+        let day = puzzle::find(2).unwrap();
+        let json = timings_json(&[(day, timings())]);
+        let expected = "{\n  \"days\": [\n    {\"day\": 2, \"title\": \"Dive!\", \"parse_ns\": 10000, \"silver_ns\": 5000, \"gold_ns\": null, \"total_ns\": 15000}\n  ],\n  \"total_ns\": 15000\n}\n";
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn run_json_test() {
+        let day = puzzle::find(4).unwrap();
+        let json = run_json(&answers()[1..], &[(day, timings())]);
+        let expected = "{\n  \"answers\": [\n    {\"day\": 4, \"title\": \"Giant Squid\", \"part\": \"gold\", \"label\": \"last winner score\", \"answer\": \"1,924\"}\n  ],\n  \"days\": [\n    {\"day\": 4, \"title\": \"Giant Squid\", \"parse_ns\": 10000, \"silver_ns\": 5000, \"gold_ns\": null, \"total_ns\": 15000}\n  ],\n  \"total_ns\": 15000\n}\n";
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(json_string("a \"b\"\\\n"), "\"a \\\"b\\\"\\\\\\n\"");
+    }
+}
@@ -0,0 +1,213 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use advent_of_code_2021::error::{Error, Result};
+
+const TEMPLATE: &str = include_str!("puzzle/dayx.rs");
+
+/// Generates `src/puzzle/dayNN.rs` from the `dayx.rs` template, creates an
+/// empty input file and registers the day in `src/puzzle/mod.rs`. Returns the
+/// files that were written.
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>> {
+    let module = format!("day{:02}", day);
+    let name = type_name(title);
+    let input = format!("{}_{}.txt", module, slug(title));
+    if name.is_empty() {
+        let message = format!("`{}` does not make a type name", title);
+        return Err(Error::InvalidModel(message));
+    }
+
+    let source_path = root.join("src/puzzle").join(format!("{}.rs", module));
+    let mod_path = root.join("src/puzzle/mod.rs");
+    let input_path = root.join("inputs").join(&input);
+    if source_path.exists() {
+        return Err(already_exists(&source_path, day));
+    }
+
+    let mod_source =
+        fs::read_to_string(&mod_path).map_err(|error| Error::from(error).in_file(&mod_path))?;
+    let mod_source = register(&mod_source, day, &name).ok_or_else(|| {
+        let error = io::Error::new(
+            ErrorKind::InvalidData,
+            "could not find where to register the day",
+        );
+        Error::from(error).in_file(&mod_path)
+    })?;
+
+    let mut written = Vec::new();
+    write(&source_path, &render(day, title, &name, &input))?;
+    written.push(source_path);
+    write(&mod_path, &mod_source)?;
+    written.push(mod_path);
+    if !input_path.exists() {
+        write(&input_path, "")?;
+        written.push(input_path);
+    }
+    Ok(written)
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).map_err(|error| Error::from(error).in_file(path))
+}
+
+fn already_exists(path: &Path, day: u8) -> Error {
+    let error = io::Error::new(
+        ErrorKind::AlreadyExists,
+        format!("day {} already exists", day),
+    );
+    Error::from(error).in_file(path)
+}
+
+/// The title goes in last, so that nothing in it is taken for a placeholder.
+fn render(day: u8, title: &str, name: &str, input: &str) -> String {
+    TEMPLATE
+        .replace("\"dayxx_name.txt\"", &format!("{:?}", input))
+        .replace("Name", name)
+        .replace("const DAY: u8 = 0;", &format!("const DAY: u8 = {};", day))
+        .replace(
+            &format!("--- Day x: {} ---", name),
+            &format!("--- Day {}: {} ---", day, title),
+        )
+        .replace(
+            &format!("const TITLE: &'static str = \"{}\";", name),
+            &format!("const TITLE: &'static str = {:?};", title),
+        )
+}
+
+/// Adds `pub mod dayNN;` and the registry entry to the source of
+/// `puzzle/mod.rs`, keeping both sorted by day.
+fn register(source: &str, day: u8, name: &str) -> Option<String> {
+    let module = format!("day{:02}", day);
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+
+    let module_day = |line: &str| day_number(line.strip_prefix("pub mod ")?);
+    let last_module = lines.iter().rposition(|line| module_day(line).is_some())?;
+    let position = lines
+        .iter()
+        .position(|line| module_day(line) > Some(day))
+        .unwrap_or(last_module + 1);
+    lines.insert(position, format!("pub mod {};", module));
+
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))?;
+    let end = start + lines[start..].iter().position(|line| line == "];")?;
+    let entry_day = |line: &str| day_number(line.trim().strip_prefix("day::<")?);
+    let position = (start + 1..end)
+        .find(|&index| entry_day(&lines[index]) > Some(day))
+        .unwrap_or(end);
+    lines.insert(position, format!("    day::<{}::{}>(),", module, name));
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// Day of a `dayNN...` module name.
+fn day_number(text: &str) -> Option<u8> {
+    text.strip_prefix("day")?.get(..2)?.parse().ok()
+}
+
+/// `Hydrothermal Venture` becomes `HydrothermalVenture`.
+fn type_name(title: &str) -> String {
+    let mut name: String = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word[..1].to_uppercase() + &word[1..])
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, 'D');
+    }
+    name
+}
+
+/// `Dive!` becomes `dive` and `Giant Squid` becomes `giant_squid`.
+fn slug(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MOD: &str = "use std::env;\n\npub mod day01;\npub mod day04;\n\npub const DAYS: &[Day] = &[\n    day::<day01::SonarSweep>(),\n    day::<day04::GiantSquid>(),\n];\n";
+
+    #[test]
+    fn names() {
+        assert_eq!(type_name("Hydrothermal Venture"), "HydrothermalVenture");
+        assert_eq!(type_name("Dive!"), "Dive");
+        assert_eq!(type_name("2D or not 2D"), "D2DOrNot2D");
+        assert_eq!(slug("Giant Squid"), "giant_squid");
+        assert_eq!(slug("Dive!"), "dive");
+    }
+
+    #[test]
+    fn register_sorted() {
+        let source = register(MOD, 2, "Dive").unwrap();
+        let expected = "use std::env;\n\npub mod day01;\npub mod day02;\npub mod day04;\n\npub const DAYS: &[Day] = &[\n    day::<day01::SonarSweep>(),\n    day::<day02::Dive>(),\n    day::<day04::GiantSquid>(),\n];\n";
+        assert_eq!(source, expected);
+    }
+
+    #[test]
+    fn register_last() {
+        let source = register(MOD, 12, "PassagePathing").unwrap();
+        assert!(source.contains("pub mod day04;\npub mod day12;\n"));
+        assert!(source
+            .contains("    day::<day04::GiantSquid>(),\n    day::<day12::PassagePathing>(),\n];"));
+    }
+
+    #[test]
+    fn register_needs_anchors() {
+        assert!(register("fn main() {}", 2, "Dive").is_none());
+    }
+
+    #[test]
+    fn render_template() {
+        let source = render(
+            5,
+            "Hydrothermal Venture",
+            "HydrothermalVenture",
+            "day05_hydrothermal_venture.txt",
+        );
+        assert!(source.starts_with("//! --- Day 5: Hydrothermal Venture ---\n"));
+        assert!(source.contains("pub struct HydrothermalVenture;"));
+        assert!(source.contains("impl Solution for HydrothermalVenture {"));
+        assert!(source.contains("const DAY: u8 = 5;"));
+        assert!(source.contains("const TITLE: &'static str = \"Hydrothermal Venture\";"));
+        assert!(source.contains("const INPUT: &'static str = \"day05_hydrothermal_venture.txt\";"));
+        assert!(source.contains("HydrothermalVenture::parse(EXAMPLE)"));
+        assert!(!source.contains("Name"));
+    }
+
+    #[test]
+    fn render_title_with_placeholders() {
+        let source = render(5, "Name Game", "NameGame", "day05_name_game.txt");
+        assert!(source.starts_with("//! --- Day 5: Name Game ---\n"));
+        assert!(source.contains("const TITLE: &'static str = \"Name Game\";"));
+        assert!(source.contains("pub struct NameGame;"));
+        assert!(source.contains("const INPUT: &'static str = \"day05_name_game.txt\";"));
+    }
+
+    #[test]
+    fn new_day_writes_files() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/puzzle")).unwrap();
+        fs::create_dir_all(root.join("inputs")).unwrap();
+        fs::write(root.join("src/puzzle/mod.rs"), MOD).unwrap();
+
+        let written = new_day(&root, 5, "Hydrothermal Venture").unwrap();
+        assert_eq!(written.len(), 3);
+        let source = fs::read_to_string(root.join("src/puzzle/day05.rs")).unwrap();
+        assert!(source.contains("pub struct HydrothermalVenture;"));
+        let mod_source = fs::read_to_string(root.join("src/puzzle/mod.rs")).unwrap();
+        assert!(mod_source.contains("    day::<day05::HydrothermalVenture>(),\n];"));
+        assert!(root.join("inputs/day05_hydrothermal_venture.txt").exists());
+        assert!(new_day(&root, 5, "Hydrothermal Venture").is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+
+use crate::sonar::{mean, median, Depth};
+
+/// Finds suspicious readings in a sonar sweep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detector {
+    /// Readings before each depth that make up its rolling window.
+    pub window: usize,
+    /// How many times the mean step of the rolling window a reading may lie
+    /// from where the window expects it before it is a spike.
+    pub spike: f64,
+    /// Standard deviations from the mean of the sweep before a reading is an
+    /// outlier.
+    pub deviations: f64,
+    /// How many times the step before it a step against the trend must be to
+    /// count as a reversal. At 0 any sign flip counts.
+    pub flip: f64,
+    /// How much of the flip the step after it must undo, 1 being all of it.
+    /// At 0 any step back with the trend counts.
+    pub revert: f64,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self {
+            window: 10,
+            spike: 20.0,
+            deviations: 3.0,
+            flip: 10.0,
+            revert: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly<D = usize> {
+    pub index: usize,
+    pub depth: D,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    /// Far from the depth expected by the rolling window before it, the
+    /// window median carried forward along the median step.
+    Spike { expected: f64 },
+    /// Far from the mean of the whole sweep.
+    Outlier { deviations: f64 },
+    /// Steps against the trend of the steps before and after it, which
+    /// reverts it.
+    Reversal,
+}
+
+impl Detector {
+    /// Every anomaly in `depths`, ordered by index. A reading can be
+    /// anomalous for more than one reason. Steps are taken exactly and only
+    /// the statistics they feed are `f64`.
+    pub fn detect<D: Depth>(&self, depths: &[D]) -> Vec<Anomaly<D>> {
+        let mut anomalies = Vec::new();
+        let anomaly = |index: usize, reason| Anomaly {
+            index,
+            depth: depths[index],
+            reason,
+        };
+
+        // Medians keep a spike from skewing the expectation of the readings
+        // right after it.
+        let window = self.window.max(2);
+        for index in window..depths.len() {
+            let readings = &depths[index - window..index];
+            let mut steps: Vec<f64> = readings
+                .windows(2)
+                .map(|pair| step(pair[0], pair[1]))
+                .collect();
+            let size = steps.iter().map(|step| step.abs()).sum::<f64>() / steps.len() as f64;
+            let step = median(&mut steps);
+            let mut readings: Vec<f64> = readings.iter().map(|depth| depth.as_f64()).collect();
+            let expected = median(&mut readings) + step * (window + 1) as f64 / 2.0;
+            if (depths[index].as_f64() - expected).abs() > self.spike * size.max(1.0) {
+                anomalies.push(anomaly(index, Reason::Spike { expected }));
+            }
+        }
+
+        let mean = mean(depths);
+        let variance = depths
+            .iter()
+            .map(|depth| (depth.as_f64() - mean).powi(2))
+            .sum::<f64>()
+            / depths.len() as f64;
+        let deviation = variance.sqrt();
+        if deviation > 0.0 {
+            for (index, &depth) in depths.iter().enumerate() {
+                let deviations = (depth.as_f64() - mean).abs() / deviation;
+                if deviations > self.deviations {
+                    anomalies.push(anomaly(index, Reason::Outlier { deviations }));
+                }
+            }
+        }
+
+        for index in 2..depths.len().saturating_sub(1) {
+            let direction = |index: usize| depths[index].cmp(&depths[index - 1]);
+            let size = |index: usize| depths[index].abs_diff(depths[index - 1]).as_f64();
+            let (before, flip, after) =
+                (direction(index - 1), direction(index), direction(index + 1));
+            let against = before.is_ne() && after == before && flip == before.reverse();
+            let large = size(index) >= self.flip * size(index - 1);
+            let undone = size(index + 1) >= self.revert * size(index);
+            if against && large && undone {
+                anomalies.push(anomaly(index, Reason::Reversal));
+            }
+        }
+
+        anomalies.sort_by_key(|anomaly| anomaly.index);
+        anomalies
+    }
+}
+
+/// The change from `from` to `to`, exact up to the rounding of its size.
+fn step<D: Depth>(from: D, to: D) -> f64 {
+    if to >= from {
+        (to - from).as_f64()
+    } else {
+        -(from - to).as_f64()
+    }
+}
+
+impl<D: Depth> fmt::Display for Anomaly<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>6}  {:>6}  ", self.index, self.depth)?;
+        match self.reason {
+            Reason::Spike { expected } => write!(f, "spike, expected about {:.1}", expected),
+            Reason::Outlier { deviations } => {
+                write!(f, "outlier {:.1} standard deviations out", deviations)
+            }
+            Reason::Reversal => write!(f, "reversal against the trend"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reasons<D>(anomalies: &[Anomaly<D>]) -> Vec<(usize, &'static str)> {
+        anomalies
+            .iter()
+            .map(|anomaly| {
+                let reason = match anomaly.reason {
+                    Reason::Spike { .. } => "spike",
+                    Reason::Outlier { .. } => "outlier",
+                    Reason::Reversal => "reversal",
+                };
+                (anomaly.index, reason)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_spike() {
+        let mut depths = vec![10usize; 20];
+        depths[10] = 50;
+        let anomalies = Detector::default().detect(&depths);
+        assert_eq!(reasons(&anomalies), [(10, "spike"), (10, "outlier")]);
+        assert_eq!(anomalies[0].reason, Reason::Spike { expected: 10.0 });
+    }
+
+    /// Flags every step against the trend that the next step goes back on.
+    fn any_reversal() -> Detector {
+        Detector {
+            flip: 0.0,
+            revert: 0.0,
+            ..Detector::default()
+        }
+    }
+
+    #[test]
+    fn reversals() {
+        let anomalies = any_reversal().detect(&[10usize, 12, 11, 13]);
+        assert_eq!(reasons(&anomalies), [(2, "reversal")]);
+        assert_eq!(anomalies[0].depth, 11);
+
+        let example = [199usize, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let anomalies = any_reversal().detect(&example);
+        assert_eq!(reasons(&anomalies), [(4, "reversal"), (8, "reversal")]);
+        assert!(Detector::default().detect(&example).is_empty());
+    }
+
+    #[test]
+    fn reversals_larger_than_the_trend() {
+        let detector = Detector {
+            flip: 1.5,
+            revert: 1.0,
+            ..Detector::default()
+        };
+        assert!(detector.detect(&[10usize, 12, 11, 13]).is_empty());
+        let depths = [10usize, 12, 14, 9, 16, 18, 17, 20, 22];
+        assert_eq!(reasons(&detector.detect(&depths)), [(3, "reversal")]);
+        // 210 to 200 is 5 times the step before it, but 207 does not undo it.
+        let example = [199usize, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert!(detector.detect(&example).is_empty());
+    }
+
+    #[test]
+    fn wide_depths() {
+        // Steps of 1 far above 2^53, where the depths themselves round in f64.
+        let base = u128::MAX - 100;
+        let depths = [base, base + 2, base + 1, base + 3];
+        let anomalies = any_reversal().detect(&depths);
+        assert_eq!(reasons(&anomalies), [(2, "reversal")]);
+        assert_eq!(anomalies[0].depth, base + 1);
+        assert_eq!(step(u128::MAX, 0), -(u128::MAX as f64));
+    }
+
+    #[test]
+    fn steady_sweeps_are_clean() {
+        let depths: Vec<usize> = (0..100).map(|index| 100 + index * 3).collect();
+        assert!(Detector::default().detect(&depths).is_empty());
+        assert!(Detector::default().detect(&[7usize; 30]).is_empty());
+        assert!(Detector::default().detect::<usize>(&[]).is_empty());
+    }
+
+    #[test]
+    fn puzzle_input_is_clean() {
+        let path = crate::puzzle::input_dir().join("day01_sonar_sweep.txt");
+        let input = std::fs::read_to_string(path).unwrap();
+        let depths: Vec<usize> = crate::util::str2vec_numbers_strict(&input).unwrap();
+        assert_eq!(depths.len(), 2000);
+        assert_eq!(Detector::default().detect(&depths), []);
+    }
+
+    #[test]
+    fn display() {
+        let anomaly = Anomaly {
+            index: 10,
+            depth: 50usize,
+            reason: Reason::Outlier { deviations: 4.36 },
+        };
+        assert_eq!(
+            anomaly.to_string(),
+            "    10      50  outlier 4.4 standard deviations out"
+        );
+    }
+}
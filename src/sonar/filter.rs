@@ -0,0 +1,265 @@
+//! Smoothing filters over depth series. Filters work on `f64` so that
+//! averages and differences need no rounding, and can be chained with
+//! [`Filter::then`] or collected into a `Vec<Box<dyn Filter>>`. Depths only
+//! become a series when every one of them is exact as an `f64`.
+
+use crate::error::{Error, Result};
+use crate::puzzle::day01::{sonar_sweep, window_sums};
+use crate::sonar::{median, Depth};
+
+/// A transformation of a depth series. The output may be shorter than the
+/// input, a window of `k` readings leaves `len - k + 1` values.
+pub trait Filter {
+    fn apply(&self, series: &[f64]) -> Vec<f64>;
+
+    /// Applies `next` to the output of this filter.
+    fn then<F: Filter>(self, next: F) -> Chain<Self, F>
+    where
+        Self: Sized,
+    {
+        Chain(self, next)
+    }
+
+    /// Increases in the filtered depths, counted like `sonar_sweep` counts
+    /// them in the raw depths.
+    fn increases<D: Depth>(&self, depths: &[D]) -> Result<usize>
+    where
+        Self: Sized,
+    {
+        Ok(increases(&self.apply(&series(depths)?)))
+    }
+}
+
+/// The depths as `f64`, or an error naming the first depth above 2^53, which
+/// would silently round.
+pub fn series<D: Depth>(depths: &[D]) -> Result<Vec<f64>> {
+    depths
+        .iter()
+        .enumerate()
+        .map(|(index, depth)| {
+            depth.exact_f64().ok_or_else(|| {
+                Error::InvalidModel(format!(
+                    "depth {} at index {} has no exact f64",
+                    depth, index
+                ))
+            })
+        })
+        .collect()
+}
+
+pub fn increases(series: &[f64]) -> usize {
+    series.windows(2).filter(|pair| pair[1] > pair[0]).count()
+}
+
+/// Sums of every window of this many readings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingSum(pub usize);
+
+/// Means of every window of this many readings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingAverage(pub usize);
+
+/// Exponential moving average, each value moves `alpha` of the way from the
+/// previous average to the reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialMovingAverage(pub f64);
+
+/// Medians of every window of this many readings, which drops single spikes
+/// that averages smear out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MedianFilter(pub usize);
+
+/// The change from each reading to the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difference;
+
+/// Two filters applied one after the other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chain<A, B>(pub A, pub B);
+
+impl Filter for MovingSum {
+    /// Counted on the exact sums of the depths rather than on a series, so
+    /// any depth will do, and a sum too large for `D` is an error.
+    fn increases<D: Depth>(&self, depths: &[D]) -> Result<usize> {
+        Ok(sonar_sweep(&window_sums(depths, self.0)?))
+    }
+
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        let window = self.0;
+        if window == 0 || series.len() < window {
+            return Vec::new();
+        }
+        let mut sum: f64 = series[..window].iter().sum();
+        let mut sums = vec![sum];
+        for (leaving, entering) in series.iter().zip(&series[window..]) {
+            sum += entering - leaving;
+            sums.push(sum);
+        }
+        sums
+    }
+}
+
+impl Filter for MovingAverage {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        let sums = MovingSum(self.0).apply(series);
+        sums.into_iter().map(|sum| sum / self.0 as f64).collect()
+    }
+}
+
+impl Filter for ExponentialMovingAverage {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        let alpha = self.0;
+        let mut averages = Vec::with_capacity(series.len());
+        let mut average = None;
+        for &value in series {
+            let next = match average {
+                None => value,
+                Some(average) => average + alpha * (value - average),
+            };
+            averages.push(next);
+            average = Some(next);
+        }
+        averages
+    }
+}
+
+impl Filter for MedianFilter {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        if self.0 == 0 {
+            return Vec::new();
+        }
+        series
+            .windows(self.0)
+            .map(|window| median(&mut window.to_vec()))
+            .collect()
+    }
+}
+
+impl Filter for Difference {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        series.windows(2).map(|pair| pair[1] - pair[0]).collect()
+    }
+}
+
+impl<A: Filter, B: Filter> Filter for Chain<A, B> {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        self.1.apply(&self.0.apply(series))
+    }
+}
+
+impl<F: Filter + ?Sized> Filter for Box<F> {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        (**self).apply(series)
+    }
+}
+
+/// Filters chosen at runtime, applied in order.
+impl Filter for Vec<Box<dyn Filter>> {
+    fn apply(&self, series: &[f64]) -> Vec<f64> {
+        self.iter()
+            .fold(series.to_vec(), |series, filter| filter.apply(&series))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::puzzle::day01::sonar_sweep_window;
+
+    const EXAMPLE: [usize; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn moving_sum() {
+        let sums = MovingSum(3).apply(&series(&EXAMPLE).unwrap());
+        assert_eq!(
+            sums,
+            [607.0, 618.0, 618.0, 617.0, 647.0, 716.0, 769.0, 792.0]
+        );
+        assert_eq!(MovingSum(3).increases(&EXAMPLE).unwrap(), 5);
+        assert!(MovingSum(11).apply(&series(&EXAMPLE).unwrap()).is_empty());
+        assert!(MovingSum(0).apply(&series(&EXAMPLE).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn moving_average() {
+        let averages = MovingAverage(2).apply(&[1.0, 3.0, 2.0]);
+        assert_eq!(averages, [2.0, 2.5]);
+    }
+
+    #[test]
+    fn exponential_moving_average() {
+        let averages = ExponentialMovingAverage(0.5).apply(&[4.0, 8.0, 0.0]);
+        assert_eq!(averages, [4.0, 6.0, 3.0]);
+        let unchanged = ExponentialMovingAverage(1.0).apply(&series(&EXAMPLE).unwrap());
+        assert_eq!(unchanged, series(&EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn median_drops_spikes() {
+        let medians = MedianFilter(3).apply(&[1.0, 2.0, 90.0, 3.0, 4.0]);
+        assert_eq!(medians, [2.0, 3.0, 4.0]);
+        assert_eq!(MedianFilter(2).apply(&[1.0, 2.0]), [1.5]);
+    }
+
+    #[test]
+    fn difference() {
+        let changes = Difference.apply(&series(&EXAMPLE).unwrap());
+        assert_eq!(changes, [1.0, 8.0, 2.0, -10.0, 7.0, 33.0, 29.0, -9.0, 3.0]);
+    }
+
+    #[test]
+    fn chains() {
+        let smoothed_changes = MovingSum(3).then(Difference);
+        assert_eq!(
+            smoothed_changes.apply(&series(&EXAMPLE).unwrap()),
+            [11.0, 0.0, -1.0, 30.0, 69.0, 53.0, 23.0]
+        );
+        let filters: Vec<Box<dyn Filter>> = vec![Box::new(MovingSum(3)), Box::new(Difference)];
+        assert_eq!(
+            filters.apply(&series(&EXAMPLE).unwrap()),
+            smoothed_changes.apply(&series(&EXAMPLE).unwrap())
+        );
+        let nothing: Vec<Box<dyn Filter>> = Vec::new();
+        assert_eq!(nothing.increases(&EXAMPLE).unwrap(), sonar_sweep(&EXAMPLE));
+    }
+
+    #[test]
+    fn wide_depths() {
+        let depths = [u64::MAX - 2, u64::MAX - 1, 1 << 53, u64::MAX];
+        let error = series(&depths).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid input: depth 18446744073709551613 at index 0 has no exact f64"
+        );
+        assert!(MovingAverage(2).increases(&depths).is_err());
+        assert_eq!(series(&depths[2..3]).unwrap(), [9007199254740992.0]);
+
+        // Sums of 2^126 + 1, 2^126 + 1 and 2^126 + 2 are all 2^126 as f64.
+        let depths = [1u128 << 126, 1, 1 << 126, 2];
+        assert_eq!(MovingSum(2).increases(&depths).unwrap(), 1);
+        let error = MovingSum(2).increases(&[u32::MAX, 1]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid input: the sum of depths 0..2 overflows u32"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn moving_sum_matches_window(depths in vec(0usize..10_000, 0..200), k in 1usize..10) {
+            let increases = sonar_sweep_window(&depths, k);
+            prop_assert_eq!(MovingSum(k).increases(&depths).unwrap(), increases);
+            prop_assert_eq!(MovingAverage(k).increases(&depths).unwrap(), increases);
+        }
+
+        #[test]
+        fn positive_differences_are_increases(depths in vec(0usize..10_000, 0..200)) {
+            let changes = Difference.apply(&series(&depths).unwrap());
+            let positive = changes.iter().filter(|&&change| change > 0.0).count();
+            prop_assert_eq!(positive, sonar_sweep(&depths));
+        }
+    }
+}
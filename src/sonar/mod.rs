@@ -0,0 +1,123 @@
+//! Analysis of sonar sweeps, the depth series of day 1, beyond counting
+//! increases.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::util::Integer;
+
+pub mod anomaly;
+pub mod filter;
+pub mod report;
+
+pub use report::SonarReport;
+
+/// Unsigned integer types a sweep can be read as, `usize` for the puzzle and
+/// up to `u128` for synthetic sweeps. Sums of depths go through
+/// [`Integer::checked_add`]; the plain operators are for arithmetic that
+/// stays between the smallest and the largest depth.
+pub trait Depth:
+    Integer
+    + Ord
+    + Default
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + Mul<Output = Self>
+{
+    const ONE: Self;
+    const MAX: Self;
+
+    fn from_usize(value: usize) -> Option<Self>;
+    fn to_usize(self) -> Option<usize>;
+
+    /// The nearest `f64`, for statistics that are fractions anyway.
+    fn as_f64(self) -> f64;
+
+    /// The `f64` of this depth, if it has one that is exact.
+    fn exact_f64(self) -> Option<f64>;
+
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_depth {
+    ($($t:ty),*) => {
+        $(
+            impl Depth for $t {
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+
+                fn from_usize(value: usize) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn exact_f64(self) -> Option<f64> {
+                    // Every integer up to 2^53 fits the mantissa of an f64.
+                    if self as u128 <= 1 << f64::MANTISSA_DIGITS {
+                        Some(self as f64)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_depth!(u32, u64, u128, usize);
+
+/// The mean of `depths`, summed as `f64`, which rounds but cannot overflow.
+fn mean<D: Depth>(depths: &[D]) -> f64 {
+    depths.iter().map(|depth| depth.as_f64()).sum::<f64>() / depths.len() as f64
+}
+
+/// The median of `values`, which are sorted in place.
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        (values[middle - 1] + values[middle]) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exact_f64() {
+        assert_eq!(u32::MAX.exact_f64(), Some(u32::MAX as f64));
+        assert_eq!((1u64 << 53).exact_f64(), Some(9007199254740992.0));
+        assert_eq!(((1u64 << 53) + 1).exact_f64(), None);
+        assert_eq!(u128::MAX.exact_f64(), None);
+        assert_eq!(7u128.abs_diff(10), 3);
+        assert_eq!(u64::from_usize(usize::MAX), Some(u64::MAX));
+        assert_eq!(u32::from_usize(usize::MAX), None);
+        assert_eq!(u128::MAX.to_usize(), None);
+    }
+
+    #[test]
+    fn mean_and_median() {
+        assert_eq!(mean(&[1usize, 2, 6]), 3.0);
+        assert_eq!(median(&mut [6.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&mut [6.0, 1.0, 2.0, 4.0]), 3.0);
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::sonar::{mean, median, Depth};
+
+pub const DEFAULT_BUCKETS: usize = 10;
+
+/// Summary statistics of a sonar sweep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SonarReport<D = usize> {
+    pub readings: usize,
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize,
+    pub longest_rise: Run,
+    pub longest_fall: Run,
+    pub largest_jump: Option<Jump<D>>,
+    pub min: D,
+    pub max: D,
+    pub mean: f64,
+    pub median: f64,
+    pub histogram: Vec<Bucket<D>>,
+}
+
+/// Readings `start..start + length` that strictly rise or strictly fall.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Run {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// The step from reading `index - 1` to reading `index`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jump<D = usize> {
+    pub index: usize,
+    pub from: D,
+    pub to: D,
+}
+
+/// The number of readings in `min..=max`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket<D = usize> {
+    pub min: D,
+    pub max: D,
+    pub count: usize,
+}
+
+impl<D: Depth> SonarReport<D> {
+    /// Reports on `depths` with a histogram of at most `buckets` equally wide
+    /// buckets.
+    pub fn new(depths: &[D], buckets: usize) -> Result<Self> {
+        if depths.is_empty() {
+            return Err(Error::NoAnswer(String::from(
+                "an empty sweep has nothing to report",
+            )));
+        }
+
+        let mut series: Vec<f64> = depths.iter().map(|depth| depth.as_f64()).collect();
+        let mut report = Self {
+            readings: depths.len(),
+            increases: 0,
+            decreases: 0,
+            plateaus: 0,
+            longest_rise: Run::default(),
+            longest_fall: Run::default(),
+            largest_jump: None,
+            min: *depths.iter().min().unwrap(),
+            max: *depths.iter().max().unwrap(),
+            mean: mean(depths),
+            median: median(&mut series),
+            histogram: histogram(depths, buckets),
+        };
+
+        let mut rise = Run::default();
+        let mut fall = Run::default();
+        for (index, pair) in depths.windows(2).enumerate() {
+            let (from, to) = (pair[0], pair[1]);
+            if to > from {
+                report.increases += 1;
+            } else if to < from {
+                report.decreases += 1;
+            } else {
+                report.plateaus += 1;
+            }
+            rise = extend(rise, index, to > from);
+            fall = extend(fall, index, to < from);
+            report.longest_rise = longer(report.longest_rise, rise);
+            report.longest_fall = longer(report.longest_fall, fall);
+
+            let jump = Jump {
+                index: index + 1,
+                from,
+                to,
+            };
+            if report
+                .largest_jump
+                .is_none_or(|largest| size(jump) > size(largest))
+            {
+                report.largest_jump = Some(jump);
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// The run ending at reading `index + 1`, given the run ending at `index`.
+fn extend(run: Run, index: usize, continues: bool) -> Run {
+    if !continues {
+        Run::default()
+    } else if run.length > 0 {
+        Run {
+            length: run.length + 1,
+            ..run
+        }
+    } else {
+        Run {
+            start: index,
+            length: 2,
+        }
+    }
+}
+
+fn longer(longest: Run, run: Run) -> Run {
+    if run.length > longest.length {
+        run
+    } else {
+        longest
+    }
+}
+
+fn size<D: Depth>(jump: Jump<D>) -> D {
+    jump.from.abs_diff(jump.to)
+}
+
+fn histogram<D: Depth>(depths: &[D], buckets: usize) -> Vec<Bucket<D>> {
+    let min = *depths.iter().min().unwrap();
+    let max = *depths.iter().max().unwrap();
+    // The depths cover `max - min + 1` values, which does not fit when they
+    // span every value of `D`, so the widths are worked out from `max - min`.
+    // Only a single bucket can be that wide.
+    let span = max - min;
+    let buckets = D::from_usize(buckets.max(1)).unwrap_or(D::MAX);
+    let Some(width) = (span / buckets).checked_add(D::ONE) else {
+        return vec![Bucket {
+            min,
+            max,
+            count: depths.len(),
+        }];
+    };
+    // Fewer than `buckets`, so the bucket numbers fit in usize, and no bucket
+    // starts past `max`.
+    let last = (span / width).to_usize().unwrap();
+    let mut histogram: Vec<Bucket<D>> = (0..=last)
+        .map(|bucket| {
+            let start = min + D::from_usize(bucket).unwrap() * width;
+            Bucket {
+                min: start,
+                max: start
+                    .checked_add(width - D::ONE)
+                    .map_or(max, |end| end.min(max)),
+                count: 0,
+            }
+        })
+        .collect();
+    for &depth in depths {
+        histogram[((depth - min) / width).to_usize().unwrap()].count += 1;
+    }
+    histogram
+}
+
+impl<D: Depth> fmt::Display for SonarReport<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "readings      {}", self.readings)?;
+        writeln!(f, "increases     {}", self.increases)?;
+        writeln!(f, "decreases     {}", self.decreases)?;
+        writeln!(f, "plateaus      {}", self.plateaus)?;
+        for (name, run) in [
+            ("longest rise", self.longest_rise),
+            ("longest fall", self.longest_fall),
+        ] {
+            match run.length {
+                0 => writeln!(f, "{}  -", name)?,
+                length => writeln!(f, "{}  {} readings from index {}", name, length, run.start)?,
+            }
+        }
+        match self.largest_jump {
+            Some(jump) => writeln!(
+                f,
+                "largest jump  {} at index {} ({} to {})",
+                size(jump),
+                jump.index,
+                jump.from,
+                jump.to
+            )?,
+            None => writeln!(f, "largest jump  -")?,
+        }
+        writeln!(
+            f,
+            "depth         min {}, max {}, mean {:.1}, median {:.1}",
+            self.min, self.max, self.mean, self.median
+        )?;
+
+        let most = self.histogram.iter().map(|bucket| bucket.count).max();
+        let digits = self.max.to_string().len();
+        for bucket in &self.histogram {
+            let bar = bucket.count * 40 / most.unwrap_or(1).max(1);
+            writeln!(
+                f,
+                "{:>width$}..={:<width$}  {:<40} {}",
+                bucket.min,
+                bucket.max,
+                "#".repeat(bar),
+                bucket.count,
+                width = digits
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: [usize; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_report() {
+        let report = SonarReport::new(&EXAMPLE, 4).unwrap();
+        assert_eq!(report.readings, 10);
+        assert_eq!(
+            (report.increases, report.decreases, report.plateaus),
+            (7, 2, 0)
+        );
+        assert_eq!(
+            report.longest_rise,
+            Run {
+                start: 0,
+                length: 4
+            }
+        );
+        assert_eq!(
+            report.longest_fall,
+            Run {
+                start: 3,
+                length: 2
+            }
+        );
+        let jump = Jump {
+            index: 6,
+            from: 207,
+            to: 240,
+        };
+        assert_eq!(report.largest_jump, Some(jump));
+        assert_eq!((report.min, report.max), (199, 269));
+        assert_eq!(report.mean, 225.6);
+        assert_eq!(report.median, 209.0);
+        let counts: Vec<(usize, usize, usize)> = report
+            .histogram
+            .iter()
+            .map(|bucket| (bucket.min, bucket.max, bucket.count))
+            .collect();
+        let expected = [(199, 216, 6), (217, 234, 0), (235, 252, 1), (253, 269, 3)];
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn flat_and_single_readings() {
+        let report = SonarReport::new(&[5usize, 5, 5], DEFAULT_BUCKETS).unwrap();
+        assert_eq!(report.plateaus, 2);
+        assert_eq!(report.longest_rise, Run::default());
+        assert_eq!(
+            report.histogram,
+            [Bucket {
+                min: 5,
+                max: 5,
+                count: 3
+            }]
+        );
+        let report = SonarReport::new(&[7usize], DEFAULT_BUCKETS).unwrap();
+        assert_eq!(report.largest_jump, None);
+        assert_eq!(report.median, 7.0);
+        assert!(SonarReport::<usize>::new(&[], DEFAULT_BUCKETS).is_err());
+    }
+
+    #[test]
+    fn histogram_covers_every_reading() {
+        let depths: Vec<usize> = (0..1000).map(|depth| depth * 7 % 1013).collect();
+        let histogram = histogram(&depths, 7);
+        assert!(histogram.len() <= 7);
+        assert_eq!(
+            histogram.iter().map(|bucket| bucket.count).sum::<usize>(),
+            1000
+        );
+        assert_eq!(histogram.last().unwrap().max, *depths.iter().max().unwrap());
+    }
+
+    #[test]
+    fn histogram_at_the_extremes() {
+        let bounds = |histogram: Vec<Bucket>| -> Vec<(usize, usize, usize)> {
+            histogram
+                .iter()
+                .map(|bucket| (bucket.min, bucket.max, bucket.count))
+                .collect()
+        };
+        let half = usize::MAX / 2;
+        assert_eq!(
+            bounds(histogram(&[0, usize::MAX], 2)),
+            [(0, half, 1), (half + 1, usize::MAX, 1)]
+        );
+        assert_eq!(bounds(histogram(&[0, usize::MAX], 1)), [(0, usize::MAX, 2)]);
+        assert_eq!(
+            bounds(histogram(&[usize::MAX, usize::MAX - 1], 10)),
+            [
+                (usize::MAX - 1, usize::MAX - 1, 1),
+                (usize::MAX, usize::MAX, 1)
+            ]
+        );
+        let report = SonarReport::new(&[0, usize::MAX], DEFAULT_BUCKETS).unwrap();
+        assert_eq!(report.histogram.len(), DEFAULT_BUCKETS);
+        assert_eq!(report.histogram.last().unwrap().max, usize::MAX);
+        assert!(report.to_string().contains("largest jump"));
+    }
+
+    #[test]
+    fn wide_depths() {
+        let report = SonarReport::new(&[u128::MAX, 0, u128::MAX - 1], 3).unwrap();
+        assert_eq!((report.min, report.max), (0, u128::MAX));
+        let jump = Jump {
+            index: 1,
+            from: u128::MAX,
+            to: 0,
+        };
+        assert_eq!(report.largest_jump, Some(jump));
+        let counts: Vec<usize> = report.histogram.iter().map(|bucket| bucket.count).collect();
+        assert_eq!(counts, [1, 0, 2]);
+        assert_eq!(report.histogram[2].max, u128::MAX);
+
+        let report = SonarReport::new(&[3u32, 1, 2], 300).unwrap();
+        assert_eq!(report.histogram.len(), 3);
+        assert_eq!(report.median, 2.0);
+    }
+
+    #[test]
+    fn display() {
+        let report = SonarReport::new(&[1usize, 3, 2], 2).unwrap();
+        let expected = "\
+readings      3
+increases     1
+decreases     1
+plateaus      0
+longest rise  2 readings from index 0
+longest fall  2 readings from index 1
+largest jump  2 at index 1 (1 to 3)
+depth         min 1, max 3, mean 2.0, median 2.0
+1..=2  ######################################## 2
+3..=3  ####################                     1
+";
+        assert_eq!(report.to_string(), expected);
+    }
+}
@@ -1,54 +1,479 @@
-#![allow(dead_code)]
-use std::fs::File;
+use std::any::type_name;
+use std::fs;
 use std::io::prelude::*;
+use std::num::ParseIntError;
+use std::path::Path;
+use std::str::FromStr;
 
-pub fn file2str(filename: &str) -> String {
-    let mut file = File::open(filename).expect("file {} not found");
+use crate::error::{Error, Result};
+
+pub fn file2str(filename: impl AsRef<Path>) -> Result<String> {
+    let path = filename.as_ref();
+    fs::read_to_string(path).map_err(|error| Error::from(error).in_file(path))
+}
+
+pub fn stdin2str() -> Result<String> {
     let mut string = String::new();
-    file.read_to_string(&mut string)
-        .expect("error reading file");
-    string
+    std::io::stdin().read_to_string(&mut string)?;
+    Ok(string)
+}
+
+/// 1-based column of `token` within `line`, `token` must be a subslice of
+/// `line`.
+pub fn column(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// A line of input without its line ending, `number` is 1-based.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// Splits on both LF and CRLF line endings. A trailing newline does not
+/// produce an extra empty line.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(index, text)| Line {
+        number: index + 1,
+        text: text.strip_suffix('\r').unwrap_or(text),
+    })
+}
+
+/// Calls `f` with every line of `reader` like [`lines`] does for a string,
+/// reusing one buffer so that the input never has to fit in memory.
+pub fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut f: impl FnMut(Line) -> Result<()>,
+) -> Result<()> {
+    let mut buffer = String::new();
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        number += 1;
+        let text = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        f(Line { number, text })?;
+    }
+}
+
+/// Groups the non-blank lines into blocks separated by one or more blank
+/// lines, like the boards of a bingo game.
+pub fn blocks(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks = Vec::new();
+    let mut block = Vec::new();
+    for line in lines(input) {
+        if !line.text.trim().is_empty() {
+            block.push(line);
+        } else if !block.is_empty() {
+            blocks.push(std::mem::take(&mut block));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+impl<'a> Line<'a> {
+    pub fn error(&self, column: usize, message: impl Into<String>) -> Error {
+        Error::parse(self.number, column, message)
+    }
+
+    /// Error pointing at `token`, which must be a subslice of this line.
+    pub fn error_at(&self, token: &str, message: impl Into<String>) -> Error {
+        self.error(column(self.text, token), message)
+    }
+
+    /// Parses `token`, a subslice of this line, reporting where it failed.
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T> {
+        token.parse::<T>().map_err(|_| {
+            let message = format!("`{}` is not a valid {}", token, type_name::<T>());
+            self.error_at(token, message)
+        })
+    }
+}
+
+/// Integer types that can be parsed from a string of any radix, with the
+/// checked and overflowing arithmetic needed to sum them.
+pub trait Integer: FromStr + Copy + PartialOrd {
+    fn from_str_radix(src: &str, radix: u32) -> std::result::Result<Self, ParseIntError>;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn overflowing_add(self, other: Self) -> (Self, bool);
+    fn overflowing_sub(self, other: Self) -> (Self, bool);
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn from_str_radix(src: &str, radix: u32) -> std::result::Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn overflowing_add(self, other: Self) -> (Self, bool) {
+                    <$t>::overflowing_add(self, other)
+                }
+
+                fn overflowing_sub(self, other: Self) -> (Self, bool) {
+                    <$t>::overflowing_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Values separated by `separator`, such as `1,2,3` or `1-2-3`. Surrounding
+/// whitespace and a trailing separator are ignored.
+pub fn separated<T: FromStr>(line: Line, separator: char) -> Result<Vec<T>> {
+    line.text
+        .trim()
+        .split_terminator(separator)
+        .map(|token| line.parse(token.trim()))
+        .collect()
+}
+
+pub fn comma_list<T: FromStr>(line: Line) -> Result<Vec<T>> {
+    separated(line, ',')
 }
 
-pub fn str2vec_digits(input: &str) -> Vec<usize> {
+/// Values separated by any amount of whitespace, such as a row of a grid.
+pub fn whitespace_list<T: FromStr>(line: Line) -> Result<Vec<T>> {
+    line.text
+        .split_whitespace()
+        .map(|token| line.parse(token))
+        .collect()
+}
+
+/// A `<keyword> <value>` line such as `forward 5`.
+pub fn keyword_value<'a, T: FromStr>(line: Line<'a>) -> Result<(&'a str, T)> {
+    let mut tokens = line.text.split_whitespace();
+    let keyword = tokens
+        .next()
+        .ok_or_else(|| line.error(1, "expected a keyword and a value"))?;
+    let value = tokens.next().ok_or_else(|| {
+        let message = format!("missing value after `{}`", keyword);
+        line.error(line.text.len() + 1, message)
+    })?;
+    if let Some(extra) = tokens.next() {
+        return Err(line.error_at(extra, format!("unexpected `{}`", extra)));
+    }
+    Ok((keyword, line.parse(value)?))
+}
+
+/// A string of `0` and `1` such as `10110`.
+pub fn binary<T: Integer>(line: Line) -> Result<T> {
+    let bits = line.text.trim();
+    if bits.is_empty() {
+        return Err(line.error(1, "expected a binary number"));
+    }
+    if let Some(index) = bits.find(|c| c != '0' && c != '1') {
+        let message = format!("`{}` is not a binary number", bits);
+        return Err(line.error(column(line.text, bits) + index, message));
+    }
+    T::from_str_radix(bits, 2).map_err(|_| {
+        let message = format!("{} bits do not fit in {}", bits.len(), type_name::<T>());
+        line.error_at(bits, message)
+    })
+}
+
+/// A block of whitespace separated values where every row has the same
+/// length.
+pub fn grid<T: FromStr>(block: &[Line]) -> Result<Vec<Vec<T>>> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    for line in block {
+        let row = whitespace_list(*line)?;
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                let message = format!("expected {} values, found {}", first.len(), row.len());
+                return Err(line.error(1, message));
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Splits input shaped as a header block followed by blank-line separated
+/// sections, like a bingo game.
+pub fn header_and_sections(input: &str) -> Result<(Vec<Line<'_>>, Vec<Vec<Line<'_>>>)> {
+    let mut blocks = blocks(input).into_iter();
+    let header = blocks
+        .next()
+        .ok_or_else(|| Error::InvalidModel(String::from("the input is empty")))?;
+    Ok((header, blocks.collect()))
+}
+
+pub fn str2vec_digits<T: From<u8>>(input: &str) -> Vec<T> {
     let string = String::from(input);
-    let mut vec: Vec<usize> = vec![];
+    let mut vec: Vec<T> = vec![];
     for c in string.chars() {
         if let Some(digit) = c.to_digit(10) {
-            vec.push(digit as usize);
+            vec.push(T::from(digit as u8));
         }
     }
     vec
 }
 
-pub fn str2vec_numbers(input: &str) -> Vec<usize> {
-    let mut vec: Vec<usize> = vec![];
-    for line in input.lines() {
-        if let Ok(digit) = line.parse::<usize>() {
-            vec.push(digit as usize);
+/// One number per line, lines that do not parse are skipped. Prefer
+/// [`str2vec_numbers_strict`] for puzzle input.
+pub fn str2vec_numbers<T: FromStr>(input: &str) -> Vec<T> {
+    let mut vec: Vec<T> = vec![];
+    for line in lines(input) {
+        if let Ok(number) = line.text.trim().parse::<T>() {
+            vec.push(number);
         }
     }
     vec
 }
 
-pub fn str2vecinception(input: &str) -> Vec<Vec<usize>> {
-    let lines = str2linevec(&input);
-    let vector = lines2vecvec(lines);
-    vector
+/// One number per line, blank lines are skipped and any other line that does
+/// not parse is an error.
+pub fn str2vec_numbers_strict<T: FromStr>(input: &str) -> Result<Vec<T>> {
+    let mut vec: Vec<T> = vec![];
+    for line in lines(input) {
+        let text = line.text.trim();
+        if !text.is_empty() {
+            vec.push(line.parse(text)?);
+        }
+    }
+    Ok(vec)
+}
+
+pub fn str2vecinception<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>> {
+    let lines = str2linevec(input);
+    lines2vecvec(lines)
 }
 
 pub fn str2linevec(string: &str) -> Vec<&str> {
-    let lines: Vec<&str> = string.split("\r\n").collect();
-    lines
+    lines(string).map(|line| line.text).collect()
+}
+
+pub fn lines2vecvec<T: FromStr>(lines: Vec<&str>) -> Result<Vec<Vec<T>>> {
+    let mut vector: Vec<Vec<T>> = Vec::new();
+    for (index, text) in lines.into_iter().enumerate() {
+        let line = Line {
+            number: index + 1,
+            text,
+        };
+        vector.push(whitespace_list(line)?);
+    }
+    Ok(vector)
+}
+
+#[cfg(test)]
+mod lines_test {
+    use super::*;
+
+    fn texts(input: &str) -> Vec<&str> {
+        lines(input).map(|line| line.text).collect()
+    }
+
+    #[test]
+    fn given_empty_return_empty() {
+        assert!(texts("").is_empty());
+    }
+
+    #[test]
+    fn splits_lf_and_crlf() {
+        assert_eq!(texts("a\nb\nc"), ["a", "b", "c"]);
+        assert_eq!(texts("a\r\nb\r\nc"), ["a", "b", "c"]);
+        assert_eq!(texts("a\r\nb\nc\r"), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn trailing_newline_is_not_a_line() {
+        assert_eq!(texts("a\nb\n"), ["a", "b"]);
+        assert_eq!(texts("a\r\nb\r\n"), ["a", "b"]);
+    }
+
+    #[test]
+    fn keeps_blank_lines_and_numbers() {
+        let expected = [
+            Line {
+                number: 1,
+                text: "a",
+            },
+            Line {
+                number: 2,
+                text: "",
+            },
+            Line {
+                number: 3,
+                text: "b",
+            },
+        ];
+        assert_eq!(lines("a\n\nb\n").collect::<Vec<Line>>(), expected);
+    }
+
+    #[test]
+    fn each_line_matches_lines() {
+        for input in ["", "a\n\nb\n", "a\r\nb\r\nc", "a\r\nb\nc\r"] {
+            let mut streamed = Vec::new();
+            for_each_line(input.as_bytes(), |line| {
+                streamed.push((line.number, line.text.to_owned()));
+                Ok(())
+            })
+            .unwrap();
+            let expected: Vec<(usize, String)> = lines(input)
+                .map(|line| (line.number, line.text.to_owned()))
+                .collect();
+            assert_eq!(streamed, expected);
+        }
+    }
+
+    #[test]
+    fn each_line_stops_at_first_error() {
+        let mut seen = 0;
+        let error = for_each_line("1\n2\n3\n".as_bytes(), |line| {
+            seen += 1;
+            match line.number {
+                2 => Err(line.error(1, "two")),
+                _ => Ok(()),
+            }
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "<input>:2:1: two");
+        assert_eq!(seen, 2);
+    }
+
+    fn block_texts(input: &str) -> Vec<Vec<&str>> {
+        blocks(input)
+            .into_iter()
+            .map(|block| block.into_iter().map(|line| line.text).collect())
+            .collect()
+    }
+
+    #[test]
+    fn blocks_split_on_blank_lines() {
+        let expected = vec![vec!["1,2,3"], vec!["1 2", "3 4"], vec!["5 6", "7 8"]];
+        assert_eq!(block_texts("1,2,3\n\n1 2\n3 4\n\n5 6\n7 8\n"), expected);
+        assert_eq!(
+            block_texts("1,2,3\r\n\r\n1 2\r\n3 4\r\n\r\n5 6\r\n7 8"),
+            expected
+        );
+    }
+
+    #[test]
+    fn blocks_ignore_extra_blank_lines() {
+        let expected = vec![vec!["a"], vec!["b"]];
+        assert_eq!(block_texts("\n\na\n\n \n\t\nb\n\n"), expected);
+        assert!(block_texts("\n\n").is_empty());
+    }
+
+    #[test]
+    fn blocks_keep_line_numbers() {
+        let blocks = blocks("a\n\nb\nc");
+        assert_eq!(
+            blocks[1][0],
+            Line {
+                number: 3,
+                text: "b"
+            }
+        );
+        assert_eq!(
+            blocks[1][1],
+            Line {
+                number: 4,
+                text: "c"
+            }
+        );
+    }
 }
 
-pub fn lines2vecvec(lines: Vec<&str>) -> Vec<Vec<usize>> {
-    let mut vector: Vec<Vec<usize>> = Vec::new();
-    for line in lines {
-        let row: Vec<usize> = line.split(' ').map(|x| x.parse().unwrap()).collect();
-        vector.push(row);
+#[cfg(test)]
+mod toolkit_test {
+    use super::*;
+
+    fn line(text: &str) -> Line<'_> {
+        Line { number: 7, text }
+    }
+
+    #[test]
+    fn separated_values() {
+        assert_eq!(separated::<u8>(line("1-2-3"), '-').unwrap(), [1, 2, 3]);
+        assert_eq!(comma_list::<i32>(line(" 4, -5,6,")).unwrap(), [4, -5, 6]);
+        assert!(comma_list::<u8>(line("")).unwrap().is_empty());
+        let error = comma_list::<u8>(line("1,300,2")).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:7:3: `300` is not a valid u8");
+    }
+
+    #[test]
+    fn whitespace_values() {
+        let expected: [u64; 3] = [22, 13, 17];
+        assert_eq!(
+            whitespace_list::<u64>(line(" 22 13  17")).unwrap(),
+            expected
+        );
+        let error = whitespace_list::<u64>(line("1 2 x")).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:7:5: `x` is not a valid u64");
+    }
+
+    #[test]
+    fn keyword_values() {
+        assert_eq!(keyword_value::<i16>(line("up -3")).unwrap(), ("up", -3));
+        let error = keyword_value::<u8>(line("down")).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:7:5: missing value after `down`");
+        let error = keyword_value::<u8>(line("down 1 2")).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:7:8: unexpected `2`");
+        assert!(keyword_value::<u8>(line("")).is_err());
+    }
+
+    #[test]
+    fn binary_values() {
+        assert_eq!(binary::<u8>(line("10110")).unwrap(), 0b10110);
+        assert_eq!(
+            binary::<u128>(line(&"1".repeat(100))).unwrap(),
+            (1 << 100) - 1
+        );
+        let error = binary::<u8>(line("10210")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "<input>:7:3: `10210` is not a binary number"
+        );
+        let error = binary::<u8>(line("111111111")).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:7:1: 9 bits do not fit in u8");
+        assert!(binary::<u8>(line("")).is_err());
+    }
+
+    #[test]
+    fn grid_values() {
+        let block = [line("1 2"), line("3 4")];
+        assert_eq!(grid::<u8>(&block).unwrap(), [[1, 2], [3, 4]]);
+        let block = [line("1 2"), line("3 4 5")];
+        let error = grid::<u8>(&block).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:7:1: expected 2 values, found 3");
+    }
+
+    #[test]
+    fn header_with_sections() {
+        let (header, sections) = header_and_sections("7,4,9\n\n1 2\n3 4\n\n5 6\n").unwrap();
+        assert_eq!(
+            header,
+            [Line {
+                number: 1,
+                text: "7,4,9"
+            }]
+        );
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            sections[1],
+            [Line {
+                number: 6,
+                text: "5 6"
+            }]
+        );
+        assert!(header_and_sections("\n\n").is_err());
     }
-    vector
 }
 
 #[cfg(test)]
@@ -56,7 +481,7 @@ mod str2vec_test {
     use super::*;
 
     fn str2vec_assert(input: &str, answer: Vec<usize>) {
-        let result = str2vec_digits(&input);
+        let result: Vec<usize> = str2vec_digits(input);
         assert_eq!(result, answer)
     }
 
@@ -79,6 +504,43 @@ mod str2vec_test {
     fn assert_stuff() {
         str2vec_assert("192837465", vec![1, 9, 2, 8, 3, 7, 4, 6, 5])
     }
+
+    #[test]
+    fn digits_into_other_types() {
+        assert_eq!(str2vec_digits::<u8>("109"), [1, 0, 9]);
+        assert_eq!(str2vec_digits::<i64>("42"), [4, 2]);
+    }
+}
+
+#[cfg(test)]
+mod str2vec_numbers_test {
+    use super::*;
+
+    #[test]
+    fn signed_numbers() {
+        assert_eq!(str2vec_numbers::<i32>("-5\n10\n-15\n"), [-5, 10, -15]);
+        assert_eq!(str2vec_numbers::<i64>("-5\r\n10"), [-5, 10]);
+    }
+
+    #[test]
+    fn wide_numbers() {
+        let input = "340282366920938463463374607431768211455\n1";
+        assert_eq!(str2vec_numbers::<u128>(input), [u128::MAX, 1]);
+    }
+
+    #[test]
+    fn lenient_skips_bad_lines() {
+        assert_eq!(str2vec_numbers::<u8>("1\nfoo\n300\n-2\n3"), [1, 3]);
+    }
+
+    #[test]
+    fn strict_reports_bad_lines() {
+        assert_eq!(str2vec_numbers_strict::<u8>("1\n\n2\n").unwrap(), [1, 2]);
+        let error = str2vec_numbers_strict::<u8>("1\n2\n-3\n4").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:3:1: `-3` is not a valid u8");
+        let error = str2vec_numbers_strict::<i32>("1\n  x1").unwrap_err();
+        assert_eq!(error.to_string(), "<input>:2:3: `x1` is not a valid i32");
+    }
 }
 
 #[cfg(test)]
@@ -89,7 +551,7 @@ mod str2vecinception_test {
     #[test]
     fn assert_1x1() {
         let string = "525";
-        let result = str2vecinception(string);
+        let result = str2vecinception::<usize>(string).unwrap();
         let expected = [[525]];
         assert_eq!(result, expected);
     }
@@ -97,15 +559,38 @@ mod str2vecinception_test {
     #[test]
     fn assert_1x2() {
         let string = "111 222\r\n333 444";
-        let result = str2vecinception(string);
+        let result = str2vecinception::<usize>(string).unwrap();
         let expected = [[111, 222], [333, 444]];
         assert_eq!(result, expected);
     }
 
+    #[test]
     fn assert_3x3() {
         let string = "1 2\r\n3\r\n4 5";
-        let result = str2vecinception(string);
+        let result = str2vecinception::<usize>(string).unwrap();
         let expected = vec![vec![1, 2], vec![3], vec![4, 5]];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn assert_signed() {
+        let string = "-1 2\n3 -4";
+        let result = str2vecinception::<i8>(string).unwrap();
+        assert_eq!(result, [[-1, 2], [3, -4]]);
+    }
+
+    #[test]
+    fn assert_lf_with_trailing_newline() {
+        let string = "111 222\n333 444\n";
+        let result = str2vecinception::<usize>(string).unwrap();
+        let expected = [[111, 222], [333, 444]];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn reports_position_of_bad_value() {
+        let string = "1 2\r\n3 x";
+        let error = str2vecinception::<usize>(string).unwrap_err();
+        assert_eq!(error.to_string(), "<input>:2:3: `x` is not a valid usize");
+    }
 }
@@ -0,0 +1,283 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use advent_of_code_2021::error::Error;
+use advent_of_code_2021::puzzle::{Day, Part};
+
+use crate::cli::Input;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The answers and timings of one run, as reported by `run --format json
+/// --time=json`.
+#[derive(Debug, Default, PartialEq)]
+struct Outcome {
+    answers: Vec<(String, String)>,
+    timings: Vec<(&'static str, Option<u128>)>,
+}
+
+/// Rebuilds and re-runs `day` whenever its source or input changes. The code
+/// being watched is not the code of this process, so every run goes through
+/// cargo: the tests of the day module, which hold the examples, and then the
+/// day itself. Cargo runs in the crate root, so the input is handed over as
+/// an absolute path.
+pub fn watch(day: &Day, input: &Input, parts: &[Part]) -> ExitCode {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let source = root.join(format!("src/puzzle/day{:02}.rs", day.number));
+    let input_path = match input {
+        Input::File(path) => path.clone(),
+        _ => day.input_path(),
+    };
+    let input_path = match fs::canonicalize(&input_path) {
+        Ok(path) => path,
+        Err(error) => {
+            eprintln!("error: {}", Error::from(error).in_file(&input_path));
+            return ExitCode::FAILURE;
+        }
+    };
+    let paths = [source, input_path];
+
+    let mut previous: Option<Outcome> = None;
+    let mut stamps = modified(&paths);
+    println!("watching {} and {}", paths[0].display(), paths[1].display());
+    loop {
+        if let Some(outcome) = run_once(&root, day, &paths[1], parts) {
+            print!("{}", diff(previous.as_ref(), &outcome));
+            previous = Some(outcome);
+        }
+        println!();
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = modified(&paths);
+            if current != stamps {
+                for (path, (now, before)) in paths.iter().zip(current.iter().zip(&stamps)) {
+                    if now != before {
+                        println!("--- {} changed ---", path.display());
+                    }
+                }
+                stamps = current;
+                break;
+            }
+        }
+    }
+}
+
+fn modified(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|data| data.modified()).ok())
+        .collect()
+}
+
+fn cargo(root: &Path, command: &str) -> Command {
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo.current_dir(root).arg(command).arg("--quiet");
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    cargo
+}
+
+fn run_once(root: &Path, day: &Day, input: &Path, parts: &[Part]) -> Option<Outcome> {
+    let tests = cargo(root, "test")
+        .args(["--lib", &format!("puzzle::day{:02}::", day.number)])
+        .output();
+    match tests {
+        Ok(output) if output.status.success() => println!("examples  pass"),
+        Ok(output) => {
+            println!("examples  FAIL");
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            return None;
+        }
+        Err(error) => {
+            eprintln!("error: could not run cargo test: {}", error);
+            return None;
+        }
+    }
+
+    let mut run = cargo(root, "run");
+    run.arg("--")
+        .args(run_args(day.number, input, parts))
+        .stderr(Stdio::inherit());
+    match run.output() {
+        Ok(output) if output.status.success() => {
+            Some(parse_outcome(&String::from_utf8_lossy(&output.stdout)))
+        }
+        Ok(_) => None,
+        Err(error) => {
+            eprintln!("error: could not run cargo run: {}", error);
+            None
+        }
+    }
+}
+
+/// The arguments of the `run` that reports the answers and timings.
+fn run_args(day: u8, input: &Path, parts: &[Part]) -> Vec<OsString> {
+    let mut args: Vec<OsString> = ["run", &day.to_string(), "--format", "json", "--time=json"]
+        .iter()
+        .map(OsString::from)
+        .collect();
+    args.push(OsString::from("--input"));
+    args.push(input.as_os_str().to_owned());
+    if let [part] = parts {
+        args.push(OsString::from("--part"));
+        args.push(OsString::from(match part {
+            Part::Silver => "silver",
+            Part::Gold => "gold",
+        }));
+    }
+    args
+}
+
+/// Reads back the one object per line JSON that `report::run_json` writes.
+fn parse_outcome(json: &str) -> Outcome {
+    let mut outcome = Outcome::default();
+    for line in json.lines().map(str::trim) {
+        if let (Some(part), Some(answer)) = (field(line, "part"), field(line, "answer")) {
+            outcome.answers.push((part, answer));
+        } else if line.contains("\"parse_ns\"") {
+            for (name, key) in [
+                ("parse", "parse_ns"),
+                ("silver", "silver_ns"),
+                ("gold", "gold_ns"),
+            ] {
+                let nanos = field(line, key).and_then(|value| value.parse().ok());
+                outcome.timings.push((name, nanos));
+            }
+        }
+    }
+    outcome
+}
+
+/// The value of `"key": value` in a flat JSON object, unquoted.
+fn field(object: &str, key: &str) -> Option<String> {
+    let start = object.find(&format!("\"{}\": ", key))? + key.len() + 4;
+    let rest = &object[start..];
+    match rest.strip_prefix('"') {
+        Some(rest) => {
+            let mut value = String::new();
+            let mut chars = rest.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => return Some(value),
+                    '\\' => value.push(chars.next()?),
+                    c => value.push(c),
+                }
+            }
+            None
+        }
+        None => {
+            let end = rest.find([',', '}']).unwrap_or(rest.len());
+            Some(rest[..end].trim().to_string())
+        }
+    }
+}
+
+fn diff(previous: Option<&Outcome>, outcome: &Outcome) -> String {
+    let mut lines = String::new();
+    for (part, answer) in &outcome.answers {
+        let before = previous.and_then(|previous| {
+            let (_, before) = previous.answers.iter().find(|(name, _)| name == part)?;
+            Some(before)
+        });
+        let change = match before {
+            None => String::new(),
+            Some(before) if before == answer => String::from("unchanged"),
+            Some(before) => format!("was {}", before),
+        };
+        lines += format!("{:<8}  {:>16}  {}", part, answer, change).trim_end();
+        lines.push('\n');
+    }
+    for (name, nanos) in &outcome.timings {
+        let Some(nanos) = nanos else { continue };
+        let before = previous.and_then(|previous| {
+            let (_, before) = previous.timings.iter().find(|(other, _)| other == name)?;
+            *before
+        });
+        let change = match before {
+            Some(before) => format!("was {}", format_nanos(before)),
+            None => String::new(),
+        };
+        lines += format!("{:<8}  {:>16}  {}", name, format_nanos(*nanos), change).trim_end();
+        lines.push('\n');
+    }
+    lines
+}
+
+fn format_nanos(nanos: u128) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const JSON: &str = "{\n  \"answers\": [\n    {\"day\": 1, \"title\": \"Sonar Sweep\", \"part\": \"silver\", \"label\": \"increases\", \"answer\": \"7\"},\n    {\"day\": 1, \"title\": \"Sonar Sweep\", \"part\": \"gold\", \"label\": \"increases\", \"answer\": \"5\"}\n  ],\n  \"days\": [\n    {\"day\": 1, \"title\": \"Sonar Sweep\", \"parse_ns\": 1500, \"silver_ns\": 200, \"gold_ns\": null, \"total_ns\": 1700}\n  ],\n  \"total_ns\": 1700\n}\n";
+
+    fn outcome() -> Outcome {
+        Outcome {
+            answers: vec![
+                (String::from("silver"), String::from("7")),
+                (String::from("gold"), String::from("5")),
+            ],
+            timings: vec![("parse", Some(1500)), ("silver", Some(200)), ("gold", None)],
+        }
+    }
+
+    #[test]
+    fn reads_run_json() {
+        assert_eq!(parse_outcome(JSON), outcome());
+    }
+
+    #[test]
+    fn run_arguments() {
+        let input = Path::new("/tmp/day02.txt");
+        let args = run_args(2, input, &[Part::Silver, Part::Gold]);
+        let expected = "run 2 --format json --time=json --input /tmp/day02.txt";
+        assert_eq!(args.join(OsString::from(" ").as_os_str()), expected);
+        let args = run_args(2, input, &[Part::Gold]);
+        assert_eq!(
+            args.join(OsString::from(" ").as_os_str()),
+            "run 2 --format json --time=json --input /tmp/day02.txt --part gold"
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let object = r#"{"title": "say \"hi\"", "answer": "1,2", "gold_ns": null}"#;
+        assert_eq!(field(object, "title").unwrap(), "say \"hi\"");
+        assert_eq!(field(object, "answer").unwrap(), "1,2");
+        assert_eq!(field(object, "gold_ns").unwrap(), "null");
+        assert_eq!(field(object, "part"), None);
+    }
+
+    #[test]
+    fn first_run_has_no_changes() {
+        let expected = "\
+silver                   7
+gold                     5
+parse                1.5µs
+silver             200.0ns
+";
+        assert_eq!(diff(None, &outcome()), expected);
+    }
+
+    #[test]
+    fn changes_against_previous_run() {
+        let mut next = outcome();
+        next.answers[1].1 = String::from("6");
+        next.timings[0].1 = Some(3000);
+        let expected = "\
+silver                   7  unchanged
+gold                     6  was 5
+parse                3.0µs  was 1.5µs
+silver             200.0ns  was 200.0ns
+";
+        assert_eq!(diff(Some(&outcome()), &next), expected);
+    }
+}
@@ -0,0 +1,160 @@
+//! Every day solved through the registry the `aoc` binary uses, against the
+//! example from the puzzle text and against the committed input.
+
+use std::fs::File;
+use std::io::BufReader;
+
+use advent_of_code_2021::puzzle::{self, day01, day02, day03, Part};
+use advent_of_code_2021::util::file2str;
+
+fn solve(number: u8, input: &str) -> Vec<String> {
+    let day = puzzle::find(number).unwrap();
+    (day.solve)(input, &[Part::Silver, Part::Gold])
+        .unwrap()
+        .answers
+        .into_iter()
+        .map(|answer| answer.value)
+        .collect()
+}
+
+fn open_input(number: u8) -> BufReader<File> {
+    let day = puzzle::find(number).unwrap();
+    BufReader::new(File::open(day.input_path()).unwrap())
+}
+
+fn solve_input(number: u8) -> Vec<String> {
+    let day = puzzle::find(number).unwrap();
+    solve(number, &file2str(day.input_path()).unwrap())
+}
+
+const DAY01_EXAMPLE: &str = "\
+199
+200
+208
+210
+200
+207
+240
+269
+260
+263
+";
+
+const DAY02_EXAMPLE: &str = "\
+forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+";
+
+const DAY03_EXAMPLE: &str = "\
+00100
+11110
+10110
+10111
+10101
+01111
+00111
+11100
+10000
+11001
+00010
+01010
+";
+
+const DAY04_EXAMPLE: &str = "\
+7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7
+";
+
+#[test]
+fn day01_example() {
+    assert_eq!(solve(1, DAY01_EXAMPLE), ["7", "5"]);
+}
+
+#[test]
+fn day01_input() {
+    assert_eq!(solve_input(1), ["1665", "1702"]);
+}
+
+#[test]
+fn day02_example() {
+    assert_eq!(solve(2, DAY02_EXAMPLE), ["150", "900"]);
+}
+
+#[test]
+fn day02_input() {
+    assert_eq!(solve_input(2), ["1882980", "1971232560"]);
+}
+
+#[test]
+fn day03_example() {
+    assert_eq!(solve(3, DAY03_EXAMPLE), ["198", "230"]);
+}
+
+#[test]
+fn day03_input() {
+    assert_eq!(solve_input(3), ["3320834", "4481199"]);
+}
+
+#[test]
+fn day04_example() {
+    assert_eq!(solve(4, DAY04_EXAMPLE), ["4512", "1924"]);
+}
+
+#[test]
+fn day04_input() {
+    assert_eq!(solve_input(4), ["31424", "23042"]);
+}
+
+#[test]
+fn tested_days_are_registered() {
+    // Taken from the `dayNN_example` tests of this file, so that `aoc new`
+    // can register a day before its tests are written.
+    let tested: Vec<u8> = include_str!("days.rs")
+        .lines()
+        .filter_map(|line| line.strip_prefix("fn day")?.strip_suffix("_example() {"))
+        .map(|number| number.parse().unwrap())
+        .collect();
+    assert!(!tested.is_empty());
+    for number in tested {
+        let registered = puzzle::DAYS.iter().any(|day| day.number == number);
+        assert!(registered, "day {} has tests but is not registered", number);
+    }
+}
+
+#[test]
+fn crlf_input() {
+    let input = DAY04_EXAMPLE.replace('\n', "\r\n");
+    assert_eq!(solve(4, &input), ["4512", "1924"]);
+}
+
+#[test]
+fn streamed_inputs() {
+    let counter = day01::sonar_sweep_reader(open_input(1)).unwrap();
+    assert_eq!((counter.increases, counter.sliding_increases), (1665, 1702));
+    let (sub, aimed) = day02::dive_reader(open_input(2)).unwrap();
+    assert_eq!(sub.horizontal_position().unwrap(), 1882980);
+    assert_eq!(aimed.horizontal_position().unwrap(), 1971232560);
+    let counts = day03::bit_counts_reader(open_input(3)).unwrap();
+    assert_eq!(counts.power_consumption(), 3320834);
+}